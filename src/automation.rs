@@ -0,0 +1,150 @@
+//! Results of the Unreal automation tests, read from the `index.json` report.
+
+use colour::{dark_green_ln, e_red_ln, yellow_ln};
+use serde::Deserialize;
+use std::{fmt::Write, io, path::Path};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Report {
+    pub succeeded: u32,
+    pub succeeded_with_warnings: u32,
+    pub failed: u32,
+    pub not_run: u32,
+    pub total_duration: f64,
+    pub tests: Vec<TestResult>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TestResult {
+    pub test_display_name: String,
+    pub full_test_path: String,
+    /// `Success`, `Fail`, `NotRun`, `Skipped` or `InProcess`.
+    pub state: String,
+    pub duration: f64,
+    pub warnings: u32,
+    pub errors: u32,
+    pub entries: Vec<ReportEntry>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ReportEntry {
+    pub event: Event,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Event {
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub message: String,
+}
+
+impl TestResult {
+    pub fn is_failed(&self) -> bool {
+        self.state == "Fail"
+    }
+
+    pub fn is_skipped(&self) -> bool {
+        matches!(self.state.as_str(), "NotRun" | "Skipped" | "InProcess")
+    }
+
+    fn messages<'a>(&'a self, event_type: &'a str) -> impl Iterator<Item = &'a str> {
+        self.entries
+            .iter()
+            .filter(move |e| e.event.event_type == event_type)
+            .map(|e| e.event.message.as_str())
+    }
+}
+
+impl Report {
+    pub fn load(dir: &Path) -> io::Result<Self> {
+        let data = std::fs::read_to_string(dir.join("index.json"))?;
+        // The editor writes the report with the UTF-8 byte order mark.
+        Ok(serde_json::from_str(data.trim_start_matches('\u{feff}'))?)
+    }
+
+    pub fn has_failures(&self) -> bool {
+        self.failed > 0 || self.tests.iter().any(TestResult::is_failed)
+    }
+
+    pub fn print(&self) {
+        for test in &self.tests {
+            if test.is_failed() {
+                e_red_ln!("FAIL {} ({:.2}s)", test.full_test_path, test.duration);
+                for message in test.messages("Error") {
+                    e_red_ln!("    {}", message);
+                }
+            } else if test.is_skipped() {
+                yellow_ln!("SKIP {}", test.full_test_path);
+            } else {
+                dark_green_ln!("PASS {} ({:.2}s)", test.full_test_path, test.duration);
+            }
+        }
+        println!(
+            "{} passed, {} passed with warnings, {} failed, {} not run in {:.2}s",
+            self.succeeded,
+            self.succeeded_with_warnings,
+            self.failed,
+            self.not_run,
+            self.total_duration
+        );
+    }
+
+    pub fn to_junit(&self, suite_name: &str) -> String {
+        let failures = self.tests.iter().filter(|t| t.is_failed()).count();
+        let skipped = self.tests.iter().filter(|t| t.is_skipped()).count();
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let _ = writeln!(
+            xml,
+            "<testsuites tests=\"{}\" failures=\"{failures}\" skipped=\"{skipped}\" time=\"{:.3}\">",
+            self.tests.len(),
+            self.total_duration
+        );
+        let _ = writeln!(
+            xml,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{failures}\" skipped=\"{skipped}\" time=\"{:.3}\">",
+            escape(suite_name),
+            self.tests.len(),
+            self.total_duration
+        );
+        for test in &self.tests {
+            let (class_name, name) = test
+                .full_test_path
+                .rsplit_once('.')
+                .unwrap_or(("", &test.full_test_path));
+            let _ = write!(
+                xml,
+                "    <testcase classname=\"{}\" name=\"{}\" time=\"{:.3}\"",
+                escape(class_name),
+                escape(name),
+                test.duration
+            );
+            if test.is_failed() {
+                let errors: Vec<&str> = test.messages("Error").collect();
+                let _ = writeln!(
+                    xml,
+                    ">\n      <failure message=\"{}\">{}</failure>\n    </testcase>",
+                    escape(errors.first().copied().unwrap_or("Test failed")),
+                    escape(&errors.join("\n"))
+                );
+            } else if test.is_skipped() {
+                xml.push_str(">\n      <skipped/>\n    </testcase>\n");
+            } else {
+                xml.push_str("/>\n");
+            }
+        }
+        xml.push_str("  </testsuite>\n</testsuites>\n");
+        xml
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
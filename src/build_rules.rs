@@ -0,0 +1,496 @@
+//! Minimal reader and editor of the `*.Build.cs` module rules.
+//!
+//! It does not evaluate C#, it only looks for the dependency lists additions.
+
+use std::path::{Path, PathBuf};
+
+use crate::{uplugin, uproject};
+
+/// Dependency added by the module rules.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dependency {
+    pub name: String,
+    pub public: bool,
+    /// Added only inside `if (Target.bBuildEditor)` or `Target.Type == TargetType.Editor` conditions.
+    pub editor_only: bool,
+}
+
+const PUBLIC_LIST: &str = "PublicDependencyModuleNames";
+const PRIVATE_LIST: &str = "PrivateDependencyModuleNames";
+
+/// `Add` or `AddRange` call on one of the dependency lists.
+struct ListCall {
+    public: bool,
+    /// Start of the list name.
+    start: usize,
+    open_paren: usize,
+    close_paren: usize,
+    is_range: bool,
+    editor_only: bool,
+}
+
+/// Replaces the comments with spaces, so byte positions in the text stay the same.
+pub fn strip_comments(content: &str) -> String {
+    fn blank(c: char, result: &mut String) {
+        if c == '\n' {
+            result.push('\n');
+        } else {
+            result.extend(std::iter::repeat_n(' ', c.len_utf8()));
+        }
+    }
+    let mut result = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            in_string = c != '"';
+            result.push(c);
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                result.push(c);
+            }
+            ('/', Some('/')) => {
+                blank(c, &mut result);
+                for c in chars.by_ref() {
+                    blank(c, &mut result);
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                blank(c, &mut result);
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    blank(c, &mut result);
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+            }
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+fn matching_paren(content: &str, open_paren: usize) -> usize {
+    let mut depth = 0;
+    for (i, c) in content[open_paren..].char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return open_paren + i;
+                }
+            }
+            _ => {}
+        }
+    }
+    content.len()
+}
+
+/// String literals in the text, with their byte ranges (including the quotes).
+fn string_literals(content: &str, offset: usize) -> Vec<(String, usize, usize)> {
+    let mut literals = vec![];
+    let mut start = None;
+    for (i, c) in content.char_indices() {
+        if c != '"' {
+            continue;
+        }
+        match start.take() {
+            None => start = Some(i),
+            Some(s) => literals.push((content[s + 1..i].to_owned(), offset + s, offset + i + 1)),
+        }
+    }
+    literals
+}
+
+fn is_editor_condition(text: &str) -> bool {
+    text.contains("if") && (text.contains("bBuildEditor") || text.contains("TargetType.Editor"))
+}
+
+/// Checks if the statement at the position is guarded by an editor only condition.
+fn is_editor_only(content: &str, pos: usize) -> bool {
+    let before = &content[..pos];
+    // Single statement `if` without braces.
+    let statement_start = before.rfind([';', '{', '}']).map_or(0, |i| i + 1);
+    if is_editor_condition(&before[statement_start..]) {
+        return true;
+    }
+    let mut depth = 0;
+    for (i, c) in before.char_indices().rev() {
+        match c {
+            '}' => depth += 1,
+            '{' if depth > 0 => depth -= 1,
+            '{' => {
+                let header_start = before[..i].rfind([';', '{', '}']).map_or(0, |i| i + 1);
+                if is_editor_condition(&before[header_start..i]) {
+                    return true;
+                }
+            }
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Finds the `Add` and `AddRange` calls on the list in the content with stripped comments,
+/// returning the start of the list name and the positions of the call parentheses.
+fn add_calls(stripped: &str, list: &str) -> Vec<(usize, usize, usize)> {
+    let mut calls = vec![];
+    for (start, _) in stripped.match_indices(list) {
+        let is_identifier_part =
+            stripped[..start].ends_with(|c: char| c.is_alphanumeric() || c == '_');
+        let rest = &stripped[start + list.len()..];
+        let Some(call) = rest.strip_prefix('.') else {
+            continue;
+        };
+        if is_identifier_part || !call.starts_with("Add") {
+            continue;
+        }
+        let Some(paren) = call.find('(') else {
+            continue;
+        };
+        let open_paren = start + list.len() + 1 + paren;
+        calls.push((start, open_paren, matching_paren(stripped, open_paren)));
+    }
+    calls
+}
+
+/// Finds the calls adding to the dependency lists in the content with stripped comments.
+fn list_calls(stripped: &str) -> Vec<ListCall> {
+    let mut calls = vec![];
+    for (list, public) in [(PUBLIC_LIST, true), (PRIVATE_LIST, false)] {
+        for (start, open_paren, close_paren) in add_calls(stripped, list) {
+            calls.push(ListCall {
+                public,
+                start,
+                open_paren,
+                close_paren,
+                is_range: stripped[start + list.len()..].starts_with(".AddRange"),
+                editor_only: is_editor_only(stripped, start),
+            });
+        }
+    }
+    calls.sort_by_key(|c| c.start);
+    calls
+}
+
+/// Names added to the list by its `Add` and `AddRange` calls, for example `ExtraModuleNames` of the `*.Target.cs` files.
+pub fn list_entries(content: &str, list: &str) -> Vec<String> {
+    let stripped = strip_comments(content);
+    add_calls(&stripped, list)
+        .into_iter()
+        .flat_map(|(_, open_paren, close_paren)| {
+            string_literals(&stripped[open_paren..close_paren], open_paren)
+        })
+        .map(|(name, _, _)| name)
+        .collect()
+}
+
+/// Reads the public and private dependencies from the `*.Build.cs` file content.
+pub fn parse_dependencies(content: &str) -> Vec<Dependency> {
+    let stripped = strip_comments(content);
+    let mut dependencies: Vec<Dependency> = vec![];
+    for call in list_calls(&stripped) {
+        let arguments = &stripped[call.open_paren..call.close_paren];
+        for (name, _, _) in string_literals(arguments, call.open_paren) {
+            if dependencies
+                .iter()
+                .any(|d| d.name == name && d.public == call.public)
+            {
+                continue;
+            }
+            dependencies.push(Dependency {
+                name,
+                public: call.public,
+                editor_only: call.editor_only,
+            });
+        }
+    }
+    dependencies
+}
+
+fn line_start(content: &str, pos: usize) -> usize {
+    content[..pos].rfind('\n').map_or(0, |i| i + 1)
+}
+
+fn line_end(content: &str, pos: usize) -> usize {
+    content[pos..].find('\n').map_or(content.len(), |i| pos + i)
+}
+
+fn indentation_at(content: &str, pos: usize) -> &str {
+    let start = line_start(content, pos);
+    let line = &content[start..];
+    &line[..line.len() - line.trim_start().len()]
+}
+
+/// Adds the dependency to the public or private list, keeping the formatting of the file.
+pub fn add_dependency(content: &str, dependency: &str, public: bool) -> Result<String, String> {
+    if let Some(existing) = parse_dependencies(content)
+        .iter()
+        .find(|d| d.name == dependency && !d.editor_only)
+    {
+        let list = if existing.public { "public" } else { "private" };
+        return Err(format!("`{dependency}` is already a {list} dependency"));
+    }
+    let stripped = strip_comments(content);
+    let calls = list_calls(&stripped);
+    let quoted = format!("\"{dependency}\"");
+
+    let range = calls
+        .iter()
+        .find(|c| c.public == public && c.is_range && !c.editor_only);
+    if let Some(call) = range {
+        let open = call.open_paren
+            + stripped[call.open_paren..]
+                .find('{')
+                .ok_or("Missing array")?;
+        let close = open + stripped[open..].find('}').ok_or("Missing array end")?;
+        let array = &stripped[open..close];
+        let literals = string_literals(array, open);
+        let Some((_, _, last_end)) = literals.last() else {
+            return Ok(format!(
+                "{}{{ {quoted} {}",
+                &content[..open],
+                &content[close..]
+            ));
+        };
+        if !array.contains('\n') {
+            return Ok(format!(
+                "{}, {quoted}{}",
+                &content[..*last_end],
+                &content[*last_end..]
+            ));
+        }
+        let indent = indentation_at(content, *last_end);
+        let after = &stripped[*last_end..close];
+        if after.trim_start().starts_with(',') {
+            let comma = last_end + after.find(',').unwrap_or(0) + 1;
+            return Ok(format!(
+                "{}\n{indent}{quoted},{}",
+                &content[..comma],
+                &content[comma..]
+            ));
+        }
+        return Ok(format!(
+            "{},\n{indent}{quoted}{}",
+            &content[..*last_end],
+            &content[*last_end..]
+        ));
+    }
+
+    let list = if public { PUBLIC_LIST } else { PRIVATE_LIST };
+    let statement = |indent: &str| format!("\n{indent}{list}.Add({quoted});");
+    let single = calls
+        .iter()
+        .rev()
+        .find(|c| c.public == public && !c.editor_only);
+    let anchor = single.or_else(|| calls.iter().rev().find(|c| !c.editor_only));
+    if let Some(call) = anchor {
+        let end = line_end(content, call.close_paren);
+        let indent = indentation_at(content, call.start);
+        return Ok(format!(
+            "{}{}{}",
+            &content[..end],
+            statement(indent),
+            &content[end..]
+        ));
+    }
+
+    // No dependency lists at all, add it at the end of the constructor.
+    let base = stripped
+        .find("base(Target)")
+        .ok_or("Could not find the module constructor")?;
+    let open = base
+        + stripped[base..]
+            .find('{')
+            .ok_or("Could not find the module constructor")?;
+    let mut depth = 0;
+    let close = stripped[open..]
+        .char_indices()
+        .find_map(|(i, c)| {
+            match c {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(open + i);
+                    }
+                }
+                _ => {}
+            }
+            None
+        })
+        .ok_or("Could not find the module constructor end")?;
+    let indent = format!("{}\t", indentation_at(content, close));
+    let insert_at = line_start(content, close).saturating_sub(1).max(open + 1);
+    Ok(format!(
+        "{}{}{}",
+        &content[..insert_at],
+        statement(&indent),
+        &content[insert_at..]
+    ))
+}
+
+/// Removes the dependency from both lists, keeping the formatting of the file.
+pub fn remove_dependency(content: &str, dependency: &str) -> Result<String, String> {
+    let stripped = strip_comments(content);
+    let mut result = content.to_owned();
+    let mut found = false;
+    // Going from the end, so the earlier positions stay valid.
+    for call in list_calls(&stripped).iter().rev() {
+        let arguments = &stripped[call.open_paren..call.close_paren];
+        let literals = string_literals(arguments, call.open_paren);
+        for (index, (name, start, end)) in literals.iter().enumerate().rev() {
+            if name != dependency {
+                continue;
+            }
+            found = true;
+            if !call.is_range {
+                // Whole `List.Add("Dependency");` statement.
+                let from = line_start(&stripped, call.start);
+                let to = (line_end(&stripped, call.close_paren) + 1).min(result.len());
+                result.replace_range(from..to, "");
+                continue;
+            }
+            let line_from = line_start(&stripped, *start);
+            let line_to = line_end(&stripped, *end);
+            let rest_of_line = stripped[*end..line_to].trim();
+            let own_line = stripped[line_from..*start].trim().is_empty()
+                && (rest_of_line.is_empty() || rest_of_line == ",");
+            if own_line {
+                result.replace_range(line_from..(line_to + 1).min(result.len()), "");
+                continue;
+            }
+            let after = &stripped[*end..];
+            let trailing = after.len() - after.trim_start().len();
+            if after.trim_start().starts_with(',') {
+                let comma_end = end + trailing + 1;
+                let spaces = stripped[comma_end..].len()
+                    - stripped[comma_end..].trim_start_matches(' ').len();
+                result.replace_range(*start..comma_end + spaces, "");
+            } else if index > 0 {
+                let previous_end = literals[index - 1].2;
+                result.replace_range(previous_end..*end, "");
+            } else {
+                // Only entry in the array, keep a single space between the braces.
+                let spaces = usize::from(stripped[*end..].starts_with(' '));
+                result.replace_range(*start..*end + spaces, "");
+            }
+        }
+    }
+    if !found {
+        return Err(format!("`{dependency}` is not a dependency of the module"));
+    }
+    Ok(result)
+}
+
+/// Path of the `*.Build.cs` file of a project or project plugin module.
+pub fn find_build_cs(project_path: &Path, module: &str) -> Option<PathBuf> {
+    let project_dir = project_path.parent()?;
+    let project = uproject::read_config(project_path).ok()?;
+    let file_name = format!("{module}.Build.cs");
+    if project.find_module(module).is_some() {
+        return Some(project_dir.join("Source").join(module).join(file_name));
+    }
+    uplugin::find_plugins(&project_dir.join("Plugins"))
+        .values()
+        .find_map(|descriptor_path| {
+            let descriptor = uplugin::read_config(descriptor_path).ok()?;
+            descriptor.Modules.iter().find(|m| m.Name == module)?;
+            Some(
+                descriptor_path
+                    .parent()?
+                    .join("Source")
+                    .join(module)
+                    .join(&file_name),
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BUILD_CS: &str = r#"public class MyGame : ModuleRules
+{
+	public MyGame(ReadOnlyTargetRules Target) : base(Target)
+	{
+		PublicDependencyModuleNames.AddRange(new string[] { "Core", "Engine" });
+
+		PrivateDependencyModuleNames.AddRange(new string[]
+		{
+			"Slate",
+			"SlateCore",
+		});
+
+		// PrivateDependencyModuleNames.Add("Commented");
+		if (Target.bBuildEditor)
+		{
+			PrivateDependencyModuleNames.Add("UnrealEd");
+		}
+	}
+}
+"#;
+
+    #[test]
+    fn parses_dependencies_skipping_comments() {
+        let names: Vec<(String, bool, bool)> = parse_dependencies(BUILD_CS)
+            .into_iter()
+            .map(|d| (d.name, d.public, d.editor_only))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                ("Core", true, false),
+                ("Engine", true, false),
+                ("Slate", false, false),
+                ("SlateCore", false, false),
+                ("UnrealEd", false, true),
+            ]
+            .map(|(n, p, e)| (n.to_owned(), p, e))
+        );
+    }
+
+    #[test]
+    fn adds_to_single_line_array() {
+        let updated = add_dependency(BUILD_CS, "UMG", true).unwrap();
+        assert!(updated.contains(r#"{ "Core", "Engine", "UMG" }"#));
+    }
+
+    #[test]
+    fn adds_to_multi_line_array_with_trailing_comma() {
+        let updated = add_dependency(BUILD_CS, "InputCore", false).unwrap();
+        assert!(updated.contains("\t\t\t\"SlateCore\",\n\t\t\t\"InputCore\",\n\t\t});"));
+    }
+
+    #[test]
+    fn refuses_existing_dependency() {
+        assert!(add_dependency(BUILD_CS, "Core", false).is_err());
+    }
+
+    #[test]
+    fn removes_and_adds_back_to_same_content() {
+        let removed = remove_dependency(BUILD_CS, "Engine").unwrap();
+        assert!(removed.contains(r#"{ "Core" }"#));
+        assert_eq!(add_dependency(&removed, "Engine", true).unwrap(), BUILD_CS);
+
+        let removed = remove_dependency(BUILD_CS, "Slate").unwrap();
+        assert!(removed.contains("{\n\t\t\t\"SlateCore\",\n\t\t});"));
+        assert!(remove_dependency(BUILD_CS, "Missing").is_err());
+    }
+
+    #[test]
+    fn removes_add_statement() {
+        let removed = remove_dependency(BUILD_CS, "UnrealEd").unwrap();
+        assert!(!removed.contains("UnrealEd"));
+        assert!(removed.contains("\t\t{\n\t\t}\n"));
+    }
+}
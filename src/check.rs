@@ -0,0 +1,217 @@
+use colour::{dark_green_ln_bold, e_red_ln, e_yellow_ln};
+use std::{
+    error::Error,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    consts,
+    editor::Editor,
+    targets::{self, TargetType},
+    uplugin, uproject,
+};
+
+/// Issue found in the project, with a hint on how to fix it.
+#[derive(Debug)]
+pub struct Problem {
+    pub message: String,
+    pub hint: String,
+}
+
+impl Problem {
+    fn new(message: impl Into<String>, hint: impl Into<String>) -> Self {
+        Problem {
+            message: message.into(),
+            hint: hint.into(),
+        }
+    }
+}
+
+/// Runs all static checks against the project and the configured engine.
+///
+/// When `targets` is empty the `Editor` target is required for projects with C++ modules.
+pub fn check_project(
+    editor: &Editor,
+    project_path: &Path,
+    targets: &[TargetType],
+) -> Result<Vec<Problem>, Box<dyn Error>> {
+    let engine_path = Path::new(&editor.config.editor_path);
+    let project_dir = project_path.parent().ok_or("Invalid project path")?;
+    let project = uproject::read_config(project_path)?;
+    let mut problems = vec![];
+
+    if !engine_path.join(consts::EDITOR).exists() {
+        problems.push(Problem::new(
+            format!(
+                "Configured engine path `{}` does not contain {}",
+                engine_path.display(),
+                consts::EDITOR
+            ),
+            "Run `uec set-editor <PATH>` or pass `--engine-path <PATH>`",
+        ));
+    }
+
+    check_engine_association(
+        editor,
+        project_dir,
+        &project.EngineAssociation,
+        &mut problems,
+    );
+
+    for module in &project.Modules {
+        let build_cs = project_dir
+            .join("Source")
+            .join(&module.Name)
+            .join(format!("{}.Build.cs", module.Name));
+        if !build_cs.exists() {
+            problems.push(Problem::new(
+                format!("Module `{}` has no {}", module.Name, build_cs.display()),
+                format!(
+                    "Create it with `uec add module {}` or remove the module from the .uproject file",
+                    module.Name
+                ),
+            ));
+        }
+    }
+
+    let mut available = uplugin::find_plugins(&project_dir.join("Plugins"));
+    available.extend(uplugin::find_plugins(
+        &engine_path.join("Engine").join("Plugins"),
+    ));
+    for plugin in project.Plugins.iter().filter(|p| p.Enabled) {
+        if !available.contains_key(&plugin.Name) {
+            problems.push(Problem::new(
+                format!("Enabled plugin `{}` could not be found", plugin.Name),
+                "Add the plugin to the project `Plugins` directory or disable it in the .uproject file",
+            ));
+        }
+    }
+
+    let required: Vec<TargetType> = if targets.is_empty() && !project.Modules.is_empty() {
+        vec![TargetType::Editor]
+    } else {
+        targets.to_vec()
+    };
+    let declared: Vec<TargetType> = targets::find_targets(project_path)
+        .unwrap_or_default()
+        .iter()
+        .map(|t| t.target_type)
+        .collect();
+    for target in required {
+        if !declared.contains(&target) {
+            problems.push(Problem::new(
+                format!(
+                    "No *.Target.cs file in {} declares a {} target",
+                    project_dir.join("Source").display(),
+                    target.as_str()
+                ),
+                format!(
+                    "Add a `Source/<Name>{}.Target.cs` file with `Type = TargetType.{};`",
+                    if target == TargetType::Game {
+                        ""
+                    } else {
+                        target.as_str()
+                    },
+                    target.as_str()
+                ),
+            ));
+        }
+    }
+
+    Ok(problems)
+}
+
+fn check_engine_association(
+    editor: &Editor,
+    project_dir: &Path,
+    association: &str,
+    problems: &mut Vec<Problem>,
+) {
+    let engine_path = Path::new(&editor.config.editor_path);
+    if association.is_empty() {
+        // Projects placed inside the engine directory do not need the association.
+        if !project_dir.starts_with(engine_path) {
+            problems.push(Problem::new(
+                "EngineAssociation is empty and the project is outside of the engine directory",
+                "Set `EngineAssociation` in the .uproject file to the engine version, for example \"5.4\"",
+            ));
+        }
+        return;
+    }
+    if association.starts_with('{') {
+        match find_registered_engine(association) {
+            None => problems.push(Problem::new(
+                format!("EngineAssociation `{association}` does not match any registered engine build"),
+                "Register the engine by running UnrealVersionSelector from it or set `EngineAssociation` to the engine version",
+            )),
+            Some(path) if path != engine_path => problems.push(Problem::new(
+                format!(
+                    "EngineAssociation `{association}` points to {}, but the configured engine is {}",
+                    path.display(),
+                    engine_path.display()
+                ),
+                format!("Pass `--engine-path {}` or run `uec set-editor`", path.display()),
+            )),
+            Some(_) => {}
+        }
+        return;
+    }
+    let Some(version) = editor.engine_version().map(|v| v.association()) else {
+        return;
+    };
+    if !association.starts_with(&version) {
+        problems.push(Problem::new(
+            format!("EngineAssociation `{association}` does not match the configured engine version {version}"),
+            format!("Set `EngineAssociation` to \"{version}\" or configure a {association} engine with `uec set-editor`"),
+        ));
+    }
+}
+
+/// Looks up a source engine build registered by UnrealVersionSelector.
+#[cfg(target_os = "windows")]
+fn find_registered_engine(association: &str) -> Option<PathBuf> {
+    let output = std::process::Command::new("reg")
+        .args([
+            "query",
+            r"HKCU\Software\Epic Games\Unreal Engine\Builds",
+            "/v",
+            association,
+        ])
+        .output()
+        .ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stdout
+        .lines()
+        .find(|l| l.trim_start().starts_with(association))
+        .and_then(|l| l.split("REG_SZ").nth(1))
+        .map(|p| PathBuf::from(p.trim()))
+}
+
+/// Looks up a source engine build registered by UnrealVersionSelector.
+#[cfg(not(target_os = "windows"))]
+fn find_registered_engine(association: &str) -> Option<PathBuf> {
+    let home = PathBuf::from(std::env::var_os("HOME")?);
+    let install_ini = if cfg!(target_os = "macos") {
+        home.join("Library/Application Support/Epic/UnrealEngine/Install.ini")
+    } else {
+        home.join(".config/Epic/UnrealEngine/Install.ini")
+    };
+    let content = std::fs::read_to_string(install_ini).ok()?;
+    content
+        .lines()
+        .filter_map(|l| l.split_once('='))
+        .find(|(key, _)| key.trim() == association)
+        .map(|(_, path)| PathBuf::from(path.trim()))
+}
+
+pub fn print_problems(problems: &[Problem]) {
+    if problems.is_empty() {
+        dark_green_ln_bold!("No problems found");
+        return;
+    }
+    for problem in problems {
+        e_red_ln!("error: {}", problem.message);
+        e_yellow_ln!("  hint: {}", problem.hint);
+    }
+    e_red_ln!("Found {} problem(s)", problems.len());
+}
@@ -1,8 +1,22 @@
 use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    ide::Ide,
+    packaging::{BuildPreset, BuildVariant},
+    targets::Configuration,
+};
 
 #[derive(Default, Debug, Serialize, Deserialize)]
 pub struct Config {
     pub editor_path: String,
+    /// Additional directories searched for project templates by the `new` command.
+    #[serde(default)]
+    pub template_dirs: Vec<String>,
 }
 
 impl Config {
@@ -14,3 +28,89 @@ impl Config {
         let _ = confy::store("uec", "config", self);
     }
 }
+
+/// Per-project settings, stored in `uec.toml` next to the `.uproject` file.
+#[derive(Default, Debug, Serialize, Deserialize)]
+pub struct ProjectConfig {
+    /// IDE used by `generate-project-files` when none is given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ide: Option<Ide>,
+    /// Combinations packaged by `build --matrix` when none are given.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub matrix: Vec<BuildVariant>,
+    /// Named sets of the `build` options, selected with `build --profile`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, BuildProfile>,
+}
+
+/// Options of the `build` command, the ones given on the command line take precedence.
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BuildProfile {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub platform: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub configuration: Option<Configuration>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matrix: Option<Vec<BuildVariant>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub share_build: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keep_builds: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preset: Option<BuildPreset>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iostore: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compressed: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prereqs: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distribution: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iterative_cooking: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skip_build: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skip_cook: Option<bool>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub maps: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub uat_args: Vec<String>,
+    /// Archive directory relative to the project directory, can use the `build` output placeholders.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+}
+
+impl ProjectConfig {
+    pub fn path(project_path: &Path) -> PathBuf {
+        project_path.with_file_name("uec.toml")
+    }
+
+    /// Loads the settings of the project, missing file gives the defaults.
+    pub fn load(project_path: &Path) -> Result<Self, Box<dyn Error>> {
+        let path = Self::path(project_path);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)?;
+        toml::from_str(&content)
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()).into())
+    }
+
+    pub fn profile(&self, name: &str) -> Result<&BuildProfile, String> {
+        self.profiles.get(name).ok_or_else(|| {
+            let names: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+            format!(
+                "Profile `{name}` is not defined in uec.toml, available profiles: {}",
+                names.join(", ")
+            )
+        })
+    }
+
+    pub fn to_toml(&self) -> Result<String, toml::ser::Error> {
+        toml::to_string_pretty(self)
+    }
+}
@@ -36,3 +36,12 @@ pub const DOTNET: &str = "Engine/Binaries/ThirdParty/DotNet/6.0.302/windows/dotn
 
 #[cfg(target_os = "windows")]
 pub const BUILD_TOOL: &str = "Engine/Binaries/DotNET/UnrealBuildTool/UnrealBuildTool.dll";
+
+pub const BUILD_VERSION: &str = "Engine/Build/Build.version";
+
+#[cfg(target_os = "windows")]
+pub const EDITOR_CMD: &str = "Engine/Binaries/Win64/UnrealEditor-Cmd.exe";
+#[cfg(target_os = "macos")]
+pub const EDITOR_CMD: &str = "Engine/Binaries/Mac/UnrealEditor-Cmd";
+#[cfg(target_os = "linux")]
+pub const EDITOR_CMD: &str = "Engine/Binaries/Linux/UnrealEditor-Cmd";
@@ -0,0 +1,262 @@
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use crate::uplugin;
+
+/// Calls `f` with each file under the path and its size.
+///
+/// Symbolic links are not followed, unreadable entries are skipped.
+pub fn for_each_file(path: &Path, f: &mut impl FnMut(&Path, u64)) {
+    let Ok(metadata) = std::fs::symlink_metadata(path) else {
+        return;
+    };
+    if !metadata.is_dir() {
+        f(path, metadata.len());
+        return;
+    }
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return;
+    };
+    for entry in entries.filter_map(|e| e.ok()) {
+        for_each_file(&entry.path(), f);
+    }
+}
+
+/// Size of the file or all files under the directory, in bytes.
+pub fn size_of(path: &Path) -> u64 {
+    let mut size = 0;
+    for_each_file(path, &mut |_, len| size += len);
+    size
+}
+
+/// Size in binary units, e.g. `1.5 GiB`.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct UsageEntry {
+    pub name: String,
+    pub path: PathBuf,
+    pub size: u64,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<UsageEntry>,
+}
+
+impl UsageEntry {
+    fn new(name: &str, path: PathBuf) -> Self {
+        UsageEntry {
+            name: name.to_owned(),
+            size: size_of(&path),
+            path,
+            children: vec![],
+        }
+    }
+
+    /// Entry of the directory with an entry for each of its subdirectories.
+    fn with_subdirectories(name: &str, path: PathBuf) -> Self {
+        let mut entry = Self::new(name, path);
+        if let Ok(dirs) = std::fs::read_dir(&entry.path) {
+            entry.children = dirs
+                .filter_map(|e| e.ok())
+                .filter(|e| e.path().is_dir())
+                .map(|e| Self::new(&e.file_name().to_string_lossy(), e.path()))
+                .collect();
+        }
+        sort_by_size(&mut entry.children);
+        entry
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExtensionUsage {
+    pub extension: String,
+    pub files: usize,
+    pub size: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DiskUsage {
+    pub project: PathBuf,
+    /// Size of the whole project directory.
+    pub total: u64,
+    pub entries: Vec<UsageEntry>,
+    pub content_extensions: Vec<ExtensionUsage>,
+    /// Caches shared by all projects using the engine, not included in the total.
+    pub engine: Vec<UsageEntry>,
+}
+
+fn sort_by_size(entries: &mut [UsageEntry]) {
+    entries.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+}
+
+/// Local derived data caches used by the engine.
+fn engine_cache_dirs(engine_dir: &Path) -> Vec<(&'static str, PathBuf)> {
+    let mut dirs = vec![(
+        "Engine DerivedDataCache",
+        engine_dir.join("Engine").join("DerivedDataCache"),
+    )];
+    let shared = if let Some(path) = std::env::var_os("UE-LocalDataCachePath") {
+        Some(PathBuf::from(path))
+    } else if cfg!(target_os = "windows") {
+        std::env::var_os("LOCALAPPDATA").map(|p| PathBuf::from(p).join("UnrealEngine"))
+    } else if cfg!(target_os = "macos") {
+        std::env::var_os("HOME").map(|p| {
+            PathBuf::from(p)
+                .join("Library")
+                .join("Application Support")
+                .join("Epic")
+                .join("UnrealEngine")
+        })
+    } else {
+        std::env::var_os("HOME").map(|p| {
+            PathBuf::from(p)
+                .join(".config")
+                .join("Epic")
+                .join("UnrealEngine")
+        })
+    };
+    if let Some(shared) = shared {
+        let shared = if shared.ends_with("UnrealEngine") {
+            shared.join("Common").join("DerivedDataCache")
+        } else {
+            shared
+        };
+        dirs.push(("Local DerivedDataCache", shared));
+    }
+    dirs
+}
+
+/// Breaks down the size of the project directory and the engine caches.
+pub fn project_usage(project_path: &Path, engine_dir: &Path) -> DiskUsage {
+    let project_dir = project_path.parent().unwrap_or(Path::new("."));
+    let content_dir = project_dir.join("Content");
+    let mut entries = vec![UsageEntry::with_subdirectories(
+        "Content",
+        content_dir.clone(),
+    )];
+    for dir in ["Intermediate", "Saved", "DerivedDataCache", "Binaries"] {
+        entries.push(UsageEntry::new(dir, project_dir.join(dir)));
+    }
+    let mut plugins = UsageEntry::new("Plugins", project_dir.join("Plugins"));
+    plugins.children = uplugin::find_plugins(&plugins.path)
+        .into_iter()
+        .filter_map(|(name, descriptor)| {
+            Some(UsageEntry::with_subdirectories(
+                &name,
+                descriptor.parent()?.to_path_buf(),
+            ))
+        })
+        .collect();
+    sort_by_size(&mut plugins.children);
+    entries.push(plugins);
+
+    let total = size_of(project_dir);
+    let listed: u64 = entries.iter().map(|e| e.size).sum();
+    entries.push(UsageEntry {
+        name: "Other".to_owned(),
+        path: project_dir.to_path_buf(),
+        size: total.saturating_sub(listed),
+        children: vec![],
+    });
+    sort_by_size(&mut entries);
+
+    let mut extensions: HashMap<String, (usize, u64)> = HashMap::new();
+    for_each_file(&content_dir, &mut |path, size| {
+        let extension = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let usage = extensions.entry(extension).or_default();
+        usage.0 += 1;
+        usage.1 += size;
+    });
+    let mut content_extensions: Vec<ExtensionUsage> = extensions
+        .into_iter()
+        .map(|(extension, (files, size))| ExtensionUsage {
+            extension,
+            files,
+            size,
+        })
+        .collect();
+    content_extensions.sort_by(|a, b| b.size.cmp(&a.size).then(a.extension.cmp(&b.extension)));
+
+    let mut engine: Vec<UsageEntry> = engine_cache_dirs(engine_dir)
+        .into_iter()
+        .filter(|(_, path)| path.exists())
+        .map(|(name, path)| UsageEntry::new(name, path))
+        .collect();
+    sort_by_size(&mut engine);
+
+    DiskUsage {
+        project: project_dir.to_path_buf(),
+        total,
+        entries,
+        content_extensions,
+        engine,
+    }
+}
+
+impl DiskUsage {
+    pub fn print(&self) {
+        fn print_entry(entry: &UsageEntry, depth: usize) {
+            println!(
+                "{:>10}  {}{}",
+                format_size(entry.size),
+                "  ".repeat(depth),
+                entry.name
+            );
+            for child in &entry.children {
+                print_entry(child, depth + 1);
+            }
+        }
+        println!(
+            "{:>10}  {}",
+            format_size(self.total),
+            self.project.display()
+        );
+        for entry in &self.entries {
+            print_entry(entry, 1);
+        }
+        if !self.content_extensions.is_empty() {
+            println!("\nContent by extension:");
+            for usage in &self.content_extensions {
+                let extension = if usage.extension.is_empty() {
+                    "(none)".to_owned()
+                } else {
+                    format!(".{}", usage.extension)
+                };
+                println!(
+                    "{:>10}  {extension} ({} files)",
+                    format_size(usage.size),
+                    usage.files
+                );
+            }
+        }
+        if !self.engine.is_empty() {
+            println!("\nEngine caches:");
+            for entry in &self.engine {
+                println!(
+                    "{:>10}  {} ({})",
+                    format_size(entry.size),
+                    entry.name,
+                    entry.path.display()
+                );
+            }
+        }
+    }
+}
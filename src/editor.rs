@@ -9,7 +9,22 @@ use std::{
     time::Instant,
 };
 
-use crate::{config::Config, consts, uproject, Cli};
+use serde::Deserialize;
+
+use crate::{
+    automation, build_rules, check,
+    config::{Config, ProjectConfig},
+    consts, disk, graph,
+    ide::{self, Ide},
+    ini,
+    manifest::{self, Manifest},
+    packaging::{self, BuildVariant, NamePattern, OutputTemplate},
+    scaffold,
+    targets::{self, Configuration, TargetType},
+    uplugin,
+    uproject::{self, LoadingPhase, ModuleType},
+    BuildArgs, CleanLevel, Cli, KeepDir, VersionArgs,
+};
 
 pub struct Editor {
     pub config: Config,
@@ -17,6 +32,8 @@ pub struct Editor {
     pub error_only: bool,
     /// No command would be run. Instead it will just output what it would run.
     dry_run: bool,
+    /// `.uproject` file used instead of searching for one.
+    project: Option<PathBuf>,
 }
 
 impl Editor {
@@ -31,7 +48,20 @@ impl Editor {
             logs: cli.save_logs.clone(),
             error_only: cli.error_only,
             dry_run: cli.dry_run,
+            project: cli.project.clone(),
+        }
+    }
+
+    /// The `.uproject` file given with `--project`, or the one found from the directory.
+    fn find_uproject_file(&self, dir: &Option<PathBuf>) -> Result<PathBuf, Box<dyn Error>> {
+        let Some(project) = &self.project else {
+            return find_file_by_extension(dir, "uproject");
+        };
+        if project.extension().is_none_or(|e| e != "uproject") {
+            return Err(format!("{} is not a .uproject file", project.display()).into());
         }
+        Ok(std::fs::canonicalize(project)
+            .map_err(|e| format!("Failed to open {}: {e}", project.display()))?)
     }
 
     pub fn build_editor_exec(base_dir: &str) -> Option<PathBuf> {
@@ -47,29 +77,492 @@ impl Editor {
         Self::build_editor_exec(&self.config.editor_path)
     }
 
-    pub fn clean_project(&self, path: &Option<PathBuf>) -> Result<(), Box<dyn Error>> {
-        let project_path = find_uproject_file(path);
-        let Ok(project_path) = project_path else {
-            panic!("PROJECT AT PATH DOES NOT EXIST!");
+    pub fn get_editor_cmd_exec(&self) -> Option<PathBuf> {
+        let editor = Path::new(&self.config.editor_path).join(consts::EDITOR_CMD);
+        editor.exists().then_some(editor)
+    }
+
+    /// Reads the version of the configured engine from its `Build.version` file.
+    pub fn engine_version(&self) -> Option<EngineVersion> {
+        let path = Path::new(&self.config.editor_path).join(consts::BUILD_VERSION);
+        let data = std::fs::read_to_string(path).ok()?;
+        serde_json::from_str(&data).ok()
+    }
+
+    /// Looks for the template by path first, then in the engine `Templates` directory and the configured template directories.
+    fn find_template(&self, template: &str) -> Result<PathBuf, Box<dyn Error>> {
+        let as_path = PathBuf::from(template);
+        if as_path.is_dir() {
+            return Ok(as_path);
+        }
+        let engine_templates = Path::new(&self.config.editor_path).join("Templates");
+        std::iter::once(engine_templates)
+            .chain(self.config.template_dirs.iter().map(PathBuf::from))
+            .map(|dir| dir.join(template))
+            .find(|dir| dir.is_dir())
+            .ok_or_else(|| format!("Could not find template `{template}`").into())
+    }
+
+    pub fn new_project(
+        &self,
+        name: &str,
+        template: &str,
+        path: &Option<PathBuf>,
+        generate_project: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        scaffold::validate_project_name(name)?;
+        let template_dir = self.find_template(template)?;
+        // Only the template directory itself, a parent project is not a template.
+        let template_project = find_file_in_dir(&template_dir, "uproject")?
+            .ok_or_else(|| format!("Template {} has no .uproject file", template_dir.display()))?;
+        let template_name = template_project
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .ok_or("Invalid template project name")?
+            .to_owned();
+        let dir = path.clone().unwrap_or(std::env::current_dir()?).join(name);
+        if dir.exists() {
+            return Err(format!("Directory {} already exists", dir.display()).into());
+        }
+        if self.dry_run {
+            cyan_ln_bold!(
+                "[DRY_RUN] Creating project {} from template {}",
+                dir.display(),
+                template_dir.display()
+            );
+            return Ok(());
+        }
+
+        let created = scaffold::copy_template(&template_dir, &dir, &template_name, name)?;
+
+        let project_path = dir.join(name).with_extension("uproject");
+        let mut project = uproject::read_config(&project_path)?;
+        if let Some(version) = self.engine_version() {
+            project.EngineAssociation = version.association();
+        }
+        uproject::write_config(&project_path, &project)?;
+
+        if !project.Modules.is_empty() {
+            // Blueprints from the template still reference the classes from the old script package.
+            let redirect = format!(
+                "\n[/Script/Engine.Engine]\n+ActiveGameNameRedirects=(OldGameName=\"/Script/{template_name}\",NewGameName=\"/Script/{name}\")\n"
+            );
+            let engine_ini = dir.join("Config").join("DefaultEngine.ini");
+            let mut file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(engine_ini)?;
+            file.write_all(redirect.as_bytes())?;
+        }
+        dark_green_ln_bold!(
+            "Created project {} with {} files",
+            project_path.display(),
+            created.len()
+        );
+
+        if generate_project && !project.Modules.is_empty() {
+            self.generate_proj_files(&Some(dir), None);
+        }
+        Ok(())
+    }
+
+    pub fn add_module(
+        &self,
+        path: &Option<PathBuf>,
+        name: &str,
+        module_type: ModuleType,
+        loading_phase: LoadingPhase,
+    ) -> Result<(), Box<dyn Error>> {
+        scaffold::validate_identifier(name)?;
+        let project_path = self.find_uproject_file(path)?;
+        let source_dir = project_path
+            .parent()
+            .ok_or("Invalid project path")?
+            .join("Source");
+        let mut project = uproject::read_config(&project_path)?;
+        if project.find_module(name).is_some() {
+            return Err(format!("Module `{name}` is already listed in the project").into());
+        }
+        if source_dir.join(name).exists() {
+            return Err(format!(
+                "Directory {} already exists",
+                source_dir.join(name).display()
+            )
+            .into());
+        }
+
+        let files = scaffold::module_files(name, module_type);
+        self.write_new_files(&source_dir, &files)?;
+
+        project
+            .Modules
+            .push(uproject::Module::new(name, module_type, loading_phase));
+        self.write_uproject(&project_path, &project)?;
+
+        for target in targets::find_target_files(&source_dir)? {
+            let content = std::fs::read_to_string(&target)?;
+            if module_type.is_editor_only()
+                && targets::declared_type(&content) != Some(TargetType::Editor)
+            {
+                continue;
+            }
+            match scaffold::add_extra_module_name(&content, name) {
+                Ok(Some(updated)) => self.write_file(&target, &updated)?,
+                Ok(None) => {
+                    if !self.error_only {
+                        print_ln_bold!("{} is already listed in {}", name, target.display());
+                    }
+                }
+                Err(e) => yellow_ln_bold!("Could not add {} to {}: {}", name, target.display(), e),
+            }
+        }
+        Ok(())
+    }
+
+    pub fn add_class(
+        &self,
+        path: &Option<PathBuf>,
+        module: &str,
+        name: &str,
+        parent: scaffold::ParentClass,
+    ) -> Result<(), Box<dyn Error>> {
+        scaffold::validate_identifier(name)?;
+        let project_path = self.find_uproject_file(path)?;
+        let project = uproject::read_config(&project_path)?;
+        if project.find_module(module).is_none() {
+            return Err(format!("Module `{module}` is not listed in the project").into());
+        }
+        let module_dir = project_path
+            .parent()
+            .ok_or("Invalid project path")?
+            .join("Source")
+            .join(module);
+        // Unreal Header Tool requires header names to be unique.
+        for extension in ["h", "cpp"] {
+            if let Some(existing) =
+                scaffold::find_file_named(&module_dir, &format!("{name}.{extension}"))
+            {
+                return Err(format!("File {} already exists", existing.display()).into());
+            }
+        }
+        let files = scaffold::class_files(module, name, parent);
+        self.write_new_files(&module_dir, &files)
+    }
+
+    pub fn new_plugin(
+        &self,
+        path: &Option<PathBuf>,
+        name: &str,
+        template: scaffold::PluginTemplate,
+        engine: bool,
+        enable: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        scaffold::validate_identifier(name)?;
+        let project_path = self.find_uproject_file(path);
+        let plugins_dir = if engine {
+            Path::new(&self.config.editor_path)
+                .join("Engine")
+                .join("Plugins")
+        } else {
+            let project_path = project_path.as_ref().map_err(|e| e.to_string())?;
+            project_path
+                .parent()
+                .ok_or("Invalid project path")?
+                .join("Plugins")
+        };
+        let plugin_dir = plugins_dir.join(name);
+        if plugin_dir.exists() {
+            return Err(format!("Directory {} already exists", plugin_dir.display()).into());
+        }
+
+        let files = scaffold::plugin_files(name, template)?;
+        self.write_new_files(&plugin_dir, &files)?;
+
+        if enable {
+            let project_path = project_path.map_err(|e| e.to_string())?;
+            let mut project = uproject::read_config(&project_path)?;
+            if project.enable_plugin(name) {
+                self.write_uproject(&project_path, &project)?;
+            }
+        }
+        if !self.error_only && !self.dry_run {
+            dark_green_ln_bold!(
+                "Plugin created, package it with: uec build-plugin {}",
+                plugin_dir.display()
+            );
+        }
+        Ok(())
+    }
+
+    /// Creates the files under the given directory, refusing to overwrite existing ones.
+    fn write_new_files(
+        &self,
+        dir: &Path,
+        files: &[(PathBuf, String)],
+    ) -> Result<(), Box<dyn Error>> {
+        if let Some((existing, _)) = files.iter().find(|(p, _)| dir.join(p).exists()) {
+            return Err(format!("File {} already exists", dir.join(existing).display()).into());
+        }
+        for (path, content) in files {
+            let path = dir.join(path);
+            if self.dry_run {
+                cyan_ln_bold!("[DRY_RUN] Creating: {}", path.display());
+                continue;
+            }
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&path, content)?;
+            if !self.error_only {
+                print_ln_bold!("Created: {}", path.display());
+            }
+        }
+        Ok(())
+    }
+
+    fn write_file(&self, path: &Path, content: &str) -> io::Result<()> {
+        if self.dry_run {
+            cyan_ln_bold!("[DRY_RUN] Updating: {}", path.display());
+            return Ok(());
+        }
+        if !self.error_only {
+            print_ln_bold!("Updating: {}", path.display());
+        }
+        std::fs::write(path, content)
+    }
+
+    fn write_uproject(&self, path: &Path, project: &uproject::Config) -> io::Result<()> {
+        if self.dry_run {
+            cyan_ln_bold!("[DRY_RUN] Updating: {}", path.display());
+            return Ok(());
+        }
+        if !self.error_only {
+            print_ln_bold!("Updating: {}", path.display());
+        }
+        uproject::write_config(path, project)
+    }
+
+    pub fn check_project(
+        &self,
+        path: &Option<PathBuf>,
+        targets: &[TargetType],
+    ) -> Result<Vec<check::Problem>, Box<dyn Error>> {
+        let project_path = self.find_uproject_file(path)?;
+        check::check_project(self, &project_path, targets)
+    }
+
+    pub fn add_dependency(
+        &self,
+        path: &Option<PathBuf>,
+        module: &str,
+        dependency: &str,
+        public: bool,
+        enable_plugin: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        let project_path = self.find_uproject_file(path)?;
+        let build_cs = build_rules::find_build_cs(&project_path, module).ok_or_else(|| {
+            format!("Module `{module}` is not part of the project or its plugins")
+        })?;
+        let content = std::fs::read_to_string(&build_cs)?;
+        let updated = build_rules::add_dependency(&content, dependency, public)?;
+        self.write_file(&build_cs, &updated)?;
+
+        let mut project = uproject::read_config(&project_path)?;
+        if project.find_module(dependency).is_some() {
+            return Ok(());
+        }
+        let project_plugins = project_path.with_file_name("Plugins");
+        let engine_plugins = Path::new(&self.config.editor_path)
+            .join("Engine")
+            .join("Plugins");
+        let plugin = uplugin::find_plugin_with_module(&project_plugins, dependency)
+            .map(|(name, descriptor)| (name, descriptor, true))
+            .or_else(|| {
+                uplugin::find_plugin_with_module(&engine_plugins, dependency)
+                    .map(|(name, descriptor)| (name, descriptor, false))
+            });
+        let Some((plugin, descriptor, is_project_plugin)) = plugin else {
+            return Ok(());
         };
-        let sln_file = project_path.with_extension("sln");
+        let is_enabled = project
+            .Plugins
+            .iter()
+            .find(|p| p.Name == plugin)
+            .map_or(descriptor.is_enabled_by_default(is_project_plugin), |p| {
+                p.Enabled
+            });
+        if is_enabled {
+            return Ok(());
+        }
+        let question = format!("Module `{dependency}` belongs to the `{plugin}` plugin which is not enabled. Enable it?");
+        if enable_plugin || confirm(&question) {
+            project.enable_plugin(&plugin);
+            self.write_uproject(&project_path, &project)?;
+        } else {
+            yellow_ln_bold!(
+                "Plugin `{}` has to be enabled in the .uproject file",
+                plugin
+            );
+        }
+        Ok(())
+    }
+
+    pub fn remove_dependency(
+        &self,
+        path: &Option<PathBuf>,
+        module: &str,
+        dependency: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        let project_path = self.find_uproject_file(path)?;
+        let build_cs = build_rules::find_build_cs(&project_path, module).ok_or_else(|| {
+            format!("Module `{module}` is not part of the project or its plugins")
+        })?;
+        let content = std::fs::read_to_string(&build_cs)?;
+        let updated = build_rules::remove_dependency(&content, dependency)?;
+        self.write_file(&build_cs, &updated)?;
+        Ok(())
+    }
+
+    /// Effective value of the config key, with the files that changed it.
+    pub fn ini_get(
+        &self,
+        path: &Option<PathBuf>,
+        file: &str,
+        section: &str,
+        key: &str,
+    ) -> Result<(Vec<String>, Vec<PathBuf>), Box<dyn Error>> {
+        let project_path = self.find_uproject_file(path)?;
+        let project_dir = project_path.parent().ok_or("Invalid project path")?;
+        let layers = ini::layer_paths(
+            Path::new(&self.config.editor_path),
+            project_dir,
+            ini::category(file),
+        );
+        Ok(ini::effective_value(&layers, section, key))
+    }
+
+    /// Sets the value in the project `Default*.ini` file, or the platform one.
+    #[allow(clippy::too_many_arguments)]
+    pub fn ini_set(
+        &self,
+        path: &Option<PathBuf>,
+        file: &str,
+        section: &str,
+        key: &str,
+        value: &str,
+        add: bool,
+        platform: bool,
+    ) -> Result<PathBuf, Box<dyn Error>> {
+        let project_path = self.find_uproject_file(path)?;
+        let config_dir = project_path.with_file_name("Config");
+        let category = ini::category(file);
+        let ini_path = if platform {
+            config_dir
+                .join(ini::INI_PLATFORM)
+                .join(format!("{}{category}.ini", ini::INI_PLATFORM))
+        } else {
+            config_dir.join(format!("Default{category}.ini"))
+        };
+        let mut config = if ini_path.exists() {
+            ini::Ini::load(&ini_path)?
+        } else {
+            ini::Ini::parse("")
+        };
+        if add {
+            config.add_unique(section, key, value);
+        } else {
+            config.set(section, key, value);
+        }
+        if let Some(parent) = ini_path.parent().filter(|_| !self.dry_run) {
+            std::fs::create_dir_all(parent)?;
+        }
+        self.write_file(&ini_path, &config.to_string())?;
+        Ok(ini_path)
+    }
+
+    pub fn list_targets(
+        &self,
+        path: &Option<PathBuf>,
+    ) -> Result<Vec<targets::Target>, Box<dyn Error>> {
+        let project_path = self.find_uproject_file(path)?;
+        Ok(targets::find_targets(&project_path)?)
+    }
+
+    pub fn module_graph(
+        &self,
+        path: &Option<PathBuf>,
+    ) -> Result<graph::ModuleGraph, Box<dyn Error>> {
+        let project_path = self.find_uproject_file(path)?;
+        graph::build_module_graph(&project_path)
+    }
+
+    /// Removes the generated files of the project and its plugins.
+    ///
+    /// With `report` the removed paths are printed with their sizes.
+    pub fn clean_project(
+        &self,
+        path: &Option<PathBuf>,
+        level: CleanLevel,
+        keep: &[KeepDir],
+        report: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        let project_path = self.find_uproject_file(path)?;
         let parent = project_path.parent().expect("");
-        self.remove_at_path(sln_file)?;
-        let dirs_to_remove = [
-            "Build",
-            "Intermediate",
-            "Saved",
-            "DerivedDataCache",
-            "PackagedProject",
-        ];
-        for dir in dirs_to_remove {
-            let path = parent.join(dir);
+        let mut dirs = vec!["Intermediate"];
+        if level == CleanLevel::Binaries {
+            dirs.push("Binaries");
+        }
+
+        let mut paths: Vec<PathBuf> = dirs.iter().map(|dir| parent.join(dir)).collect();
+        let mut plugins: Vec<PathBuf> = uplugin::find_plugins(&parent.join("Plugins"))
+            .into_values()
+            .filter_map(|descriptor| descriptor.parent().map(Path::to_path_buf))
+            .collect();
+        plugins.sort();
+        for plugin in plugins {
+            paths.extend(dirs.iter().map(|dir| plugin.join(dir)));
+        }
+        if level == CleanLevel::All {
+            paths.push(project_path.with_extension("sln"));
+            paths.push(parent.join("Build"));
+            paths.push(parent.join("PackagedProject"));
+            if !keep.contains(&KeepDir::Saved) {
+                paths.push(parent.join("Saved"));
+            }
+            if !keep.contains(&KeepDir::Ddc) {
+                paths.push(parent.join("DerivedDataCache"));
+            }
+        }
+
+        let mut total = 0;
+        for path in paths.iter().filter(|p| p.exists()) {
+            let size = if report { disk::size_of(path) } else { 0 };
             self.remove_at_path(path)?;
+            if report {
+                total += size;
+                println!("{:>10}  {}", disk::format_size(size), path.display());
+            }
+        }
+        if report {
+            let summary = format!("{:>10}  Total", disk::format_size(total));
+            if self.dry_run {
+                cyan_ln_bold!("[DRY_RUN] {}", summary);
+            } else {
+                print_ln_bold!("{}", summary);
+            }
         }
 
         Ok(())
     }
 
+    pub fn disk_usage(&self, path: &Option<PathBuf>) -> Result<disk::DiskUsage, Box<dyn Error>> {
+        let project_path = self.find_uproject_file(path)?;
+        Ok(disk::project_usage(
+            &project_path,
+            Path::new(&self.config.editor_path),
+        ))
+    }
+
     pub fn build_engine_from_source(&self, dir: &Option<PathBuf>) {
         let start = Instant::now();
         // TODO: Unix support.
@@ -117,11 +610,14 @@ impl Editor {
         let _ = std::process::Command::new(path.to_str().unwrap()).spawn();
     }
 
-    pub fn build_editor_project(&self, path: &Option<PathBuf>) {
-        let project_path = find_uproject_file(path);
-        let Ok(project_path) = project_path else {
-            panic!("PROJECT AT PATH DOES NOT EXIST!");
-        };
+    pub fn build_editor_project(
+        &self,
+        path: &Option<PathBuf>,
+        target: &Option<String>,
+    ) -> Result<(), Box<dyn Error>> {
+        let project_path = self.find_uproject_file(path)?;
+        let targets = targets::find_targets(&project_path)?;
+        let project_name = project_name(&project_path);
         let project_path = project_path
             .to_str()
             .unwrap()
@@ -131,22 +627,32 @@ impl Editor {
         let p = format!("-Project={}", &project_path);
         let build_path = Path::new(&self.config.editor_path).join(consts::BUILD_SCRIPT);
 
-        println!("Building project: {}", &project_path);
-        let uproject = uproject::read_config(&project_path).unwrap();
-        let module = uproject.find_editor_module().unwrap();
+        // Blueprint only projects use the precompiled editor.
+        let built = if targets.is_empty() && target.is_none() {
+            true
+        } else {
+            let target = targets::select_target(
+                &targets,
+                TargetType::Editor,
+                &project_name,
+                target.as_deref(),
+            )?;
+            println!("Building project: {} ({})", &project_path, target.name);
 
-        let mut bind = Command::new("cmd");
-        let cmd = bind
-            .args(["/C", (build_path.to_str().unwrap())])
-            .arg(&module.Name)
-            .arg("Win64")
-            .arg("Development")
-            .arg(&p)
-            .arg("-UsePrecompiled")
-            .arg("-WaitMutex")
-            .arg("-FromMsBuild");
+            let mut bind = Command::new("cmd");
+            let cmd = bind
+                .args(["/C", (build_path.to_str().unwrap())])
+                .arg(&target.name)
+                .arg("Win64")
+                .arg("Development")
+                .arg(&p)
+                .arg("-UsePrecompiled")
+                .arg("-WaitMutex")
+                .arg("-FromMsBuild");
+            cmd.run_with_async_logs(self).success()
+        };
 
-        if cmd.run_with_async_logs(self).success() {
+        if built {
             let path = self
                 .get_editor_exec()
                 .expect("Editor at path does not exists");
@@ -156,44 +662,375 @@ impl Editor {
                 .arg("-skipcompile")
                 .run_in_bg();
         }
+        Ok(())
     }
 
+    /// Packages the project with `BuildCookRun`.
+    ///
+    /// Matrix combinations are archived to their own subdirectories,
+    /// returns `false` when any of them failed.
     pub fn build_project(
         &self,
         path: &Option<PathBuf>,
         output_path: &Option<PathBuf>,
-    ) -> Result<ExitStatus, Box<dyn Error>> {
-        let archived_dir = output_path.clone().unwrap_or_else(|| {
-            let p = find_uproject_file(path).unwrap();
-
-            Path::new(p.parent().unwrap()).join("CookedBuild")
-        });
-        let Ok(project_path) = find_uproject_file(path) else {
+        build: &BuildArgs,
+        version: &VersionArgs,
+    ) -> Result<bool, Box<dyn Error>> {
+        let Ok(project_path) = self.find_uproject_file(path) else {
             return Err("PROJECT AT GIVEN PATH DOES NOT EXIST".into());
         };
+        let project_config = ProjectConfig::load(&project_path)?;
+        let mut output_path = output_path.clone();
+        let profiled;
+        let build = match &build.profile {
+            Some(name) => {
+                let profile = project_config.profile(name)?;
+                if output_path.is_none() {
+                    output_path = profile
+                        .output
+                        .as_ref()
+                        .map(|o| project_path.with_file_name(o));
+                }
+                profiled = build.with_profile(profile);
+                &profiled
+            }
+            None => build,
+        };
+        let template = OutputTemplate::new(
+            &output_path.unwrap_or_else(|| project_path.with_file_name("CookedBuild")),
+        );
+        let build_template = template.build_dir();
+        if build.keep_builds.is_some() && build_template.is_none() {
+            return Err(
+                "--keep-builds requires `{version}`, `{git_sha}` or `{date}` in the output path"
+                    .into(),
+            );
+        }
         let project_arg =
             format!("-project={}", project_path.to_str().unwrap()).replace("\\\\?\\", "");
 
-        let arch = format!(
-            "-archivedirectory={}",
-            archived_dir.to_str().expect("Failed to get project path.")
-        )
-        .replace("\\\\?\\", "");
-        let args: Vec<&str> = vec![
-            "BuildCookRun",
-            &arch,
-            &project_arg,
-            "-utf8output",
-            "-platform=Win64",
-            "-noP4",
-            "-nodebuginfo",
-            "-cook",
-            "-build",
-            "-stage",
-            "-archive",
-            "-pak",
-        ];
-        self.run_uat(path, args)
+        let variants = match &build.matrix {
+            None => vec![BuildVariant::new(build.platform(), build.configuration())],
+            Some(matrix) if !matrix.is_empty() => matrix.clone(),
+            Some(_) => {
+                if project_config.matrix.is_empty() {
+                    return Err("No matrix given and none set in the project uec.toml".into());
+                }
+                project_config.matrix.clone()
+            }
+        };
+        let targets = targets::find_targets(&project_path)?;
+        let project_name = project_name(&project_path);
+        let mut game_target_arg = None;
+        let has_client = variants.iter().any(|v| !v.server);
+        if has_client && (!targets.is_empty() || build.target.is_some()) {
+            let target = targets::select_target(
+                &targets,
+                TargetType::Game,
+                &project_name,
+                build.target.as_deref(),
+            )?;
+            game_target_arg = Some(format!("-target={}", target.name));
+        }
+        let mut server_target_arg = None;
+        if variants.iter().any(|v| v.server) {
+            let target = targets::select_target(&targets, TargetType::Server, &project_name, None)?;
+            server_target_arg = Some(format!("-servertarget={}", target.name));
+        }
+
+        let project_version = match &version.project_version {
+            Some(version) => version.clone(),
+            None => read_project_version(&project_path),
+        };
+        let commit = git_commit(project_path.parent().ok_or("Invalid project path")?);
+        let git_sha = if template.uses("git_sha") {
+            let commit = commit
+                .as_ref()
+                .ok_or("Failed to read the git commit of the project")?;
+            commit.chars().take(7).collect()
+        } else {
+            String::new()
+        };
+        let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+        let mut archive_dirs = vec![];
+        for variant in &variants {
+            let placeholder = |name: &str| match name {
+                "project" => Ok(project_name.clone()),
+                "platform" => Ok(variant.full_platform()),
+                "config" => Ok(variant.configuration.as_str().to_owned()),
+                "version" if project_version.is_empty() => Err(
+                    "The output path uses `{version}` but the project has no `ProjectVersion`, \
+                     pass it with --version"
+                        .to_owned(),
+                ),
+                "version" => Ok(project_version.clone()),
+                "git_sha" => Ok(git_sha.clone()),
+                "date" => Ok(date.clone()),
+                _ => Err(format!(
+                    "Unknown placeholder `{{{name}}}` in the output path"
+                )),
+            };
+            let mut archive_dir = template.expand(placeholder)?;
+            if build.matrix.is_some() && !(template.uses("platform") && template.uses("config")) {
+                archive_dir.push(variant.dir_name());
+            }
+            let build_dir = match &build_template {
+                Some(build_template) => {
+                    let dir = build_template.expand(placeholder)?;
+                    if build.keep_builds.is_some() {
+                        check_builds_dir(&dir, &project_path)?;
+                    }
+                    Some((dir, build_template.name_pattern(placeholder)?))
+                }
+                None => None,
+            };
+            archive_dirs.push((archive_dir, build_dir));
+        }
+
+        let stamp = if version.is_set() {
+            Some(self.stamp_version(&project_path, version)?)
+        } else {
+            None
+        };
+        let options = build.package.options();
+        let mut failed_shared_builds = vec![];
+        if build.share_build() && !options.skip_build {
+            for (configuration, platform_args) in packaging::shared_build_args(&variants) {
+                let mut args = vec![
+                    "BuildCookRun".to_owned(),
+                    project_arg.clone(),
+                    "-utf8output".to_owned(),
+                ];
+                let has_client = !platform_args.iter().any(|a| a == "-noclient");
+                let has_server = platform_args.iter().any(|a| a == "-server");
+                args.extend(platform_args);
+                args.extend(["-noP4".to_owned(), "-build".to_owned()]);
+                args.extend(game_target_arg.iter().filter(|_| has_client).cloned());
+                args.extend(server_target_arg.iter().filter(|_| has_server).cloned());
+                if !self.run_uat(path, args).is_ok_and(|s| s.success()) {
+                    failed_shared_builds.push(configuration);
+                }
+            }
+        }
+
+        let manifest = |variant: &BuildVariant| Manifest {
+            project: project_name.clone(),
+            engine_version: self.engine_version().map(|v| v.full()),
+            platform: variant.full_platform(),
+            configuration: variant.configuration.as_str().to_owned(),
+            git_commit: commit.clone(),
+            files: vec![],
+        };
+        let mut results = vec![];
+        for (variant, (archive_dir, _)) in variants.iter().zip(&archive_dirs) {
+            let start = Instant::now();
+            let passed = !failed_shared_builds.contains(&variant.configuration) && {
+                let mut args = vec![
+                    "BuildCookRun".to_owned(),
+                    format!("-archivedirectory={}", archive_dir.display()).replace("\\\\?\\", ""),
+                    project_arg.clone(),
+                    "-utf8output".to_owned(),
+                ];
+                args.extend(variant.uat_args());
+                let target_arg = if variant.server {
+                    &server_target_arg
+                } else {
+                    &game_target_arg
+                };
+                args.extend(target_arg.iter().cloned());
+                args.extend(options.uat_args(build.share_build()));
+                self.run_uat(path, args).is_ok_and(|s| s.success())
+            };
+            // Failing to record the build fails the variant, the ini file is still restored below.
+            let passed = passed
+                && self
+                    .record_build(archive_dir, stamp.as_ref(), variant, manifest(variant))
+                    .inspect_err(|e| {
+                        e_red_ln!(
+                            "Failed to record the build in {}: {}",
+                            archive_dir.display(),
+                            e
+                        )
+                    })
+                    .is_ok();
+            results.push(packaging::VariantResult {
+                variant: variant.clone(),
+                passed,
+                duration: start.elapsed(),
+            });
+        }
+        if let Some(stamp) = stamp {
+            if version.restore_ini {
+                stamp.restore(self)?;
+            }
+        }
+        let mut builds: Vec<(&PathBuf, &NamePattern, bool)> = vec![];
+        for ((_, build_dir), result) in archive_dirs.iter().zip(&results) {
+            let Some((build_dir, pattern)) = build_dir else {
+                continue;
+            };
+            match builds.iter_mut().find(|(dir, _, _)| *dir == build_dir) {
+                Some((_, _, passed)) => *passed &= result.passed,
+                None => builds.push((build_dir, pattern, result.passed)),
+            }
+        }
+        for (build_dir, pattern, _) in builds.iter().filter(|(_, _, passed)| *passed) {
+            if let Err(e) = self.link_latest_build(build_dir) {
+                yellow_ln_bold!(
+                    "Could not link the latest build to {}: {}",
+                    build_dir.display(),
+                    e
+                );
+            }
+            if let Some(keep) = build.keep_builds {
+                self.remove_old_builds(build_dir, pattern, keep)?;
+            }
+        }
+        if build.matrix.is_some() {
+            packaging::print_summary(&results);
+        }
+        Ok(results.iter().all(|r| r.passed))
+    }
+
+    /// Saves the version and the manifest next to the packaged build.
+    fn record_build(
+        &self,
+        archive_dir: &Path,
+        stamp: Option<&VersionStamp>,
+        variant: &BuildVariant,
+        manifest: Manifest,
+    ) -> io::Result<()> {
+        if let Some(stamp) = stamp.filter(|_| !self.dry_run) {
+            stamp.record(archive_dir)?;
+        }
+        self.write_manifest(archive_dir, variant, manifest)
+    }
+
+    /// Writes the manifest with the files of the variant into the archive directory.
+    ///
+    /// Only the platform directory is listed, the archive directory can keep the builds of other platforms.
+    fn write_manifest(
+        &self,
+        archive_dir: &Path,
+        variant: &BuildVariant,
+        mut manifest: Manifest,
+    ) -> io::Result<()> {
+        let path = archive_dir.join(manifest::MANIFEST_FILE);
+        if self.dry_run {
+            cyan_ln_bold!("[DRY_RUN] Writing: {}", path.display());
+            return Ok(());
+        }
+        if !self.error_only {
+            print_ln_bold!("Writing: {}", path.display());
+        }
+        let names = variant.archive_dir_names();
+        let mut roots: Vec<String> = names
+            .iter()
+            .filter(|name| archive_dir.join(name).is_dir())
+            .cloned()
+            .collect();
+        let roots = if roots.is_empty() {
+            yellow_ln_bold!(
+                "No {} directory in {}, listing all of its files in the manifest",
+                names.join(" or "),
+                archive_dir.display()
+            );
+            None
+        } else {
+            roots.push(VERSION_RECORD_FILE.to_owned());
+            Some(roots)
+        };
+        manifest.files = manifest::list_files(archive_dir, roots.as_deref())?;
+        manifest.write(archive_dir)
+    }
+
+    /// Points the `latest` link next to the build directory at it.
+    fn link_latest_build(&self, build_dir: &Path) -> io::Result<()> {
+        let Some(name) = build_dir.file_name() else {
+            return Ok(());
+        };
+        let link = builds_dir(build_dir).join(LATEST_BUILD_LINK);
+        if self.dry_run {
+            cyan_ln_bold!(
+                "[DRY_RUN] Linking: {} -> {}",
+                link.display(),
+                name.to_string_lossy()
+            );
+            return Ok(());
+        }
+        if link.symlink_metadata().is_ok() {
+            // Links to directories are removed as directories on Windows.
+            std::fs::remove_file(&link).or_else(|_| std::fs::remove_dir(&link))?;
+        }
+        symlink_dir(Path::new(name), &link)
+    }
+
+    /// Removes the builds next to the build directory, except for the `keep` newest ones.
+    ///
+    /// Only the directories with names matching the output template are considered builds.
+    fn remove_old_builds(
+        &self,
+        build_dir: &Path,
+        pattern: &NamePattern,
+        keep: usize,
+    ) -> io::Result<()> {
+        let dir = builds_dir(build_dir);
+        if !dir.is_dir() {
+            return Ok(());
+        }
+        let mut builds = vec![];
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            let name = entry.file_name();
+            let is_build = name.to_str().is_some_and(|n| pattern.matches(n));
+            if metadata.is_dir() && is_build && Some(name.as_os_str()) != build_dir.file_name() {
+                builds.push((metadata.modified()?, entry.path()));
+            }
+        }
+        builds.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+        for (_, path) in builds.into_iter().skip(keep.saturating_sub(1)) {
+            self.remove_at_path(path)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the version into `DefaultGame.ini`, remembering the original content.
+    fn stamp_version(
+        &self,
+        project_path: &Path,
+        args: &VersionArgs,
+    ) -> Result<VersionStamp, Box<dyn Error>> {
+        let ini_path = project_path
+            .with_file_name("Config")
+            .join("DefaultGame.ini");
+        let original = std::fs::read_to_string(&ini_path).ok();
+        let mut config = ini::Ini::parse(original.as_deref().unwrap_or_default());
+        if let Some(version) = &args.project_version {
+            config.set(PROJECT_SETTINGS_SECTION, "ProjectVersion", version);
+        }
+        let build_id = if args.stamp_git {
+            let commit = git_commit(project_path.parent().ok_or("Invalid project path")?)
+                .ok_or("Failed to read the git commit of the project")?;
+            config.set(PROJECT_SETTINGS_SECTION, "BuildId", &commit);
+            Some(commit)
+        } else {
+            None
+        };
+        let version = config
+            .get(PROJECT_SETTINGS_SECTION, "ProjectVersion")
+            .pop()
+            .unwrap_or_default();
+        if let Some(parent) = ini_path.parent().filter(|_| !self.dry_run) {
+            std::fs::create_dir_all(parent)?;
+        }
+        self.write_file(&ini_path, &config.to_string())?;
+        Ok(VersionStamp {
+            ini_path,
+            original,
+            version,
+            build_id,
+        })
     }
 
     pub fn build_plugin(&self, uplugin_path: &Option<PathBuf>, output_dir: &Option<PathBuf>) {
@@ -241,7 +1078,7 @@ impl Editor {
         let mut cmd: &mut Command = bind.args(["/C", (build_path.to_str().unwrap())]).args(args);
 
         if pass_project_path {
-            let Ok(project_path) = find_uproject_file(path) else {
+            let Ok(project_path) = self.find_uproject_file(path) else {
                 return Err("PROJECT AT GIVEN PATH DOES NOT EXIST".into());
             };
             let project_arg =
@@ -252,11 +1089,280 @@ impl Editor {
         Ok(exit_code)
     }
 
-    pub fn generate_proj_files(&self, path: &Option<PathBuf>) {
-        let project_path = find_uproject_file(path);
+    /// Runs the automation tests headlessly and reads their report.
+    ///
+    /// Returns `None` in dry run, as there is no report to read.
+    pub fn run_tests(
+        &self,
+        path: &Option<PathBuf>,
+        filter: &str,
+        report_dir: &Option<PathBuf>,
+        junit: &Option<PathBuf>,
+    ) -> Result<Option<automation::Report>, Box<dyn Error>> {
+        let project_path = self.find_uproject_file(path)?;
+        let editor_cmd = self
+            .get_editor_cmd_exec()
+            .ok_or("Editor at path does not exists")?;
+        let report_dir = report_dir.clone().unwrap_or_else(|| {
+            project_path
+                .with_file_name("Saved")
+                .join("Automation")
+                .join("uec")
+        });
+        // Leftovers from the previous run would hide a crash of the editor.
+        self.remove_at_path(report_dir.join("index.json"))?;
+
+        let status = Command::new(editor_cmd)
+            .arg(&project_path)
+            .engine_arg("ExecCmds", &format!("Automation RunTests {filter}; Quit"))
+            .engine_arg("ReportExportPath", &report_dir.display().to_string())
+            .args([
+                "-nullrhi",
+                "-unattended",
+                "-nopause",
+                "-nosplash",
+                "-NoSound",
+            ])
+            .run_with_async_logs(self);
+        if self.dry_run {
+            return Ok(None);
+        }
+        let report = automation::Report::load(&report_dir).map_err(|e| {
+            format!(
+                "Failed to read the test report from {} ({e}), editor exited with {status}",
+                report_dir.display()
+            )
+        })?;
+        let junit = junit
+            .clone()
+            .unwrap_or_else(|| report_dir.join("junit.xml"));
+        std::fs::write(&junit, report.to_junit(&project_name(&project_path)))?;
+        if !self.error_only {
+            print_ln_bold!("JUnit report: {}", junit.display());
+        }
+        Ok(Some(report))
+    }
+
+    /// Runs the commandlet in the headless editor for the project.
+    pub fn run_commandlet<I, S>(
+        &self,
+        path: &Option<PathBuf>,
+        name: &str,
+        args: I,
+    ) -> Result<ExitStatus, Box<dyn Error>>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let project_path = self.find_uproject_file(path)?;
+        let editor_cmd = self
+            .get_editor_cmd_exec()
+            .ok_or("Editor at path does not exists")?;
+        let status = Command::new(editor_cmd)
+            .arg(&project_path)
+            .arg(format!("-run={name}"))
+            .args([
+                "-unattended",
+                "-nopause",
+                "-nosplash",
+                "-NoSound",
+                "-utf8output",
+            ])
+            .args(args)
+            .run_with_async_logs(self);
+        Ok(status)
+    }
+
+    /// Runs the editor Python script headlessly.
+    ///
+    /// Returns `false` when the editor failed or the script raised an error.
+    pub fn run_python_script(
+        &self,
+        path: &Option<PathBuf>,
+        script: &Path,
+        args: &[String],
+        enable_plugin: bool,
+    ) -> Result<bool, Box<dyn Error>> {
+        let project_path = self.find_uproject_file(path)?;
+        let editor_cmd = self
+            .get_editor_cmd_exec()
+            .ok_or("Editor at path does not exists")?;
+        let script = script
+            .canonicalize()
+            .map_err(|e| format!("Script {} not found: {e}", script.display()))?;
+
+        let mut project = uproject::read_config(&project_path)?;
+        let is_enabled = project
+            .Plugins
+            .iter()
+            .any(|p| p.Name == PYTHON_PLUGIN && p.Enabled);
+        if !is_enabled {
+            let question = format!("Plugin `{PYTHON_PLUGIN}` is not enabled. Enable it?");
+            if !(enable_plugin || self.dry_run || confirm(&question)) {
+                return Err(format!(
+                    "Plugin `{PYTHON_PLUGIN}` has to be enabled in the .uproject file"
+                )
+                .into());
+            }
+            project.enable_plugin(PYTHON_PLUGIN);
+            self.write_uproject(&project_path, &project)?;
+        }
+
+        // The log is read after the run only to point at the errors raised by the script.
+        let log_path = project_path
+            .with_file_name("Saved")
+            .join("Logs")
+            .join("uec-python.log");
+        self.remove_at_path(&log_path)?;
+        let mut command_line = vec![script.to_str().unwrap().replace("\\\\?\\", "")];
+        command_line.extend(args.iter().cloned());
+        let status = Command::new(editor_cmd)
+            .arg(&project_path)
+            .engine_arg("ExecutePythonScript", &command_line.join(" "))
+            .engine_arg("abslog", &log_path.display().to_string())
+            .args([
+                "-unattended",
+                "-nopause",
+                "-nosplash",
+                "-NoSound",
+                "-stdout",
+            ])
+            .run_with_async_logs(self);
+        if self.dry_run {
+            return Ok(true);
+        }
+        if let Ok(log) = std::fs::read_to_string(&log_path) {
+            for line in log.lines().filter(|l| l.contains("LogPython: Error:")) {
+                e_red_ln!("{}", line);
+            }
+        }
+        Ok(status.success())
+    }
+
+    /// Generates `compile_commands.json` for the editor target in the project directory.
+    ///
+    /// With `project_only` the entries of engine files are dropped.
+    pub fn generate_compile_db(
+        &self,
+        path: &Option<PathBuf>,
+        target: &Option<String>,
+        platform: &str,
+        configuration: Configuration,
+        project_only: bool,
+    ) -> Result<PathBuf, Box<dyn Error>> {
+        let project_path = self.find_uproject_file(path)?;
+        let project_dir = project_path.parent().ok_or("Invalid project path")?;
+        let targets = targets::find_targets(&project_path)?;
+        let target = targets::select_target(
+            &targets,
+            TargetType::Editor,
+            &project_name(&project_path),
+            target.as_deref(),
+        )?;
+        let database = project_dir.join("compile_commands.json");
+        let build_path = Path::new(&self.config.editor_path).join(consts::BUILD_SCRIPT);
+        let status = script_command(&build_path)
+            .arg("-Mode=GenerateClangDatabase")
+            .arg(format!("-project={}", project_path.display()).replace("\\\\?\\", ""))
+            .arg(format!("-OutputDir={}", project_dir.display()).replace("\\\\?\\", ""))
+            .arg(&target.name)
+            .arg(platform)
+            .arg(configuration.as_str())
+            .run_with_async_logs(self);
+        if self.dry_run {
+            return Ok(database);
+        }
+        if !status.success() {
+            return Err(format!("UnrealBuildTool exited with {status}").into());
+        }
+        // Engines older than 5.2 ignore `-OutputDir` and write the file to the engine root.
+        let engine_database = Path::new(&self.config.editor_path).join("compile_commands.json");
+        if !database.exists() && engine_database.exists() {
+            std::fs::copy(&engine_database, &database)?;
+            std::fs::remove_file(&engine_database)?;
+        }
+        if project_only {
+            let content = std::fs::read_to_string(&database)?;
+            let mut entries: Vec<serde_json::Value> = serde_json::from_str(&content)?;
+            let project_dir = PathBuf::from(project_dir.to_str().unwrap().replace("\\\\?\\", ""));
+            let roots = [project_dir.join("Source"), project_dir.join("Plugins")];
+            entries.retain(|entry| {
+                entry["file"]
+                    .as_str()
+                    .is_some_and(|file| roots.iter().any(|root| Path::new(file).starts_with(root)))
+            });
+            std::fs::write(&database, serde_json::to_string_pretty(&entries)?)?;
+        }
+        Ok(database)
+    }
+
+    /// Writes VS Code tasks and debugger configurations for the project to `.vscode`.
+    ///
+    /// Entries added by uec are replaced, the other ones are kept.
+    pub fn setup_vscode(&self, path: &Option<PathBuf>) -> Result<(), Box<dyn Error>> {
+        let project_path = self.find_uproject_file(path)?;
+        let editor_exec = self
+            .get_editor_exec()
+            .ok_or("Editor at path does not exists")?;
+        let clean = |p: &Path| PathBuf::from(p.to_str().unwrap().replace("\\\\?\\", ""));
+        let vscode_dir = project_path.with_file_name(".vscode");
+        if !self.dry_run {
+            std::fs::create_dir_all(&vscode_dir)?;
+        }
+
+        let files = [
+            ("tasks.json", "2.0.0", "tasks", "label", ide::vscode_tasks()),
+            (
+                "launch.json",
+                "0.2.0",
+                "configurations",
+                "name",
+                ide::vscode_launch_configurations(&clean(&editor_exec), &clean(&project_path)),
+            ),
+        ];
+        for (file_name, version, array, id_field, entries) in files {
+            let file = vscode_dir.join(file_name);
+            let existing = std::fs::read_to_string(&file).ok();
+            let content =
+                ide::merge_vscode_entries(existing.as_deref(), version, array, id_field, entries)
+                    .map_err(|e| format!("Failed to read {}: {e}", file.display()))?;
+            self.write_file(&file, &content)?;
+        }
+        Ok(())
+    }
+
+    /// Generates the IDE project files.
+    ///
+    /// The IDE given explicitly is remembered in the project settings and used when none is given.
+    pub fn generate_proj_files(&self, path: &Option<PathBuf>, ide: Option<Ide>) {
+        let project_path = self.find_uproject_file(path);
         let Ok(project_path) = project_path else {
             panic!("PROJECT AT PATH DOES NOT EXIST!");
         };
+        let mut project_config =
+            ProjectConfig::load(&project_path).unwrap_or_else(|e| panic!("{}", e));
+        if ide.is_some() && ide != project_config.ide {
+            project_config.ide = ide;
+            let content = project_config
+                .to_toml()
+                .expect("Failed to serialize the project settings");
+            self.write_file(&ProjectConfig::path(&project_path), &content)
+                .expect("Failed to save the project settings");
+        }
+
+        let format = match project_config.ide {
+            Some(ide) => match ide.project_file_format() {
+                Some(format) => Some(format),
+                None => {
+                    dark_green_ln_bold!(
+                        "No project files needed, open {} in the IDE",
+                        project_path.display()
+                    );
+                    return;
+                }
+            },
+            None => None,
+        };
 
         let p = format!(
             "-project={}",
@@ -277,6 +1383,9 @@ impl Editor {
             .arg("-game")
             .arg("-rocket")
             .arg("-progress");
+        if let Some(format) = format {
+            cmd.arg(format!("-ProjectFileFormat={format}"));
+        }
 
         cmd.run_with_async_logs(self);
     }
@@ -304,46 +1413,246 @@ impl Editor {
     }
 }
 
-fn find_uproject_file(dir: &Option<PathBuf>) -> Result<PathBuf, Box<dyn Error>> {
-    find_file_by_extension(dir, "uproject")
+const PROJECT_SETTINGS_SECTION: &str = "/Script/EngineSettings.GeneralProjectSettings";
+const PYTHON_PLUGIN: &str = "PythonScriptPlugin";
+const LATEST_BUILD_LINK: &str = "latest";
+const VERSION_RECORD_FILE: &str = "version.json";
+
+/// Refuses to remove old builds from a directory containing the project or the current directory.
+fn check_builds_dir(build_dir: &Path, project_path: &Path) -> Result<(), String> {
+    let Ok(dir) = std::fs::canonicalize(builds_dir(build_dir)) else {
+        // Nothing to remove from a directory that does not exist yet.
+        return Ok(());
+    };
+    let contains = |path: Option<&Path>| {
+        path.and_then(|p| std::fs::canonicalize(p).ok())
+            .is_some_and(|p| p.starts_with(&dir))
+    };
+    if contains(project_path.parent()) || contains(std::env::current_dir().ok().as_deref()) {
+        return Err(format!(
+            "--keep-builds would remove directories from {}, which contains the project or the current directory. \
+             Put the builds in their own directory, e.g. `Builds/{{version}}`",
+            dir.display()
+        ));
+    }
+    Ok(())
+}
+
+/// Directory containing the build directory and the other builds.
+fn builds_dir(build_dir: &Path) -> &Path {
+    build_dir
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."))
 }
 
+#[cfg(windows)]
+fn symlink_dir(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::windows::fs::symlink_dir(target, link)
+}
+
+#[cfg(not(windows))]
+fn symlink_dir(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+/// `ProjectVersion` from the project settings, empty when not set.
+fn read_project_version(project_path: &Path) -> String {
+    let ini_path = project_path
+        .with_file_name("Config")
+        .join("DefaultGame.ini");
+    let content = std::fs::read_to_string(ini_path).unwrap_or_default();
+    ini::Ini::parse(&content)
+        .get(PROJECT_SETTINGS_SECTION, "ProjectVersion")
+        .pop()
+        .unwrap_or_default()
+}
+
+/// Version written into the project config for the build.
+struct VersionStamp {
+    ini_path: PathBuf,
+    /// Content of the file before stamping, `None` when it did not exist.
+    original: Option<String>,
+    version: String,
+    build_id: Option<String>,
+}
+
+impl VersionStamp {
+    /// Saves the version next to the packaged build.
+    fn record(&self, archive_dir: &Path) -> io::Result<()> {
+        let record = serde_json::json!({
+            "ProjectVersion": self.version,
+            "BuildId": self.build_id,
+        });
+        std::fs::create_dir_all(archive_dir)?;
+        std::fs::write(
+            archive_dir.join(VERSION_RECORD_FILE),
+            serde_json::to_string_pretty(&record)? + "\n",
+        )
+    }
+
+    fn restore(&self, editor: &Editor) -> io::Result<()> {
+        match &self.original {
+            Some(original) => editor.write_file(&self.ini_path, original),
+            None => editor.remove_at_path(&self.ini_path),
+        }
+    }
+}
+
+/// Full hash of the commit checked out in the directory.
+pub fn git_commit(dir: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(dir)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
+
+/// Content of the engine `Build.version` file.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct EngineVersion {
+    pub major_version: u32,
+    pub minor_version: u32,
+    pub patch_version: u32,
+    #[serde(default)]
+    pub changelist: u32,
+    #[serde(default)]
+    pub branch_name: String,
+}
+
+impl EngineVersion {
+    /// Value used by the `EngineAssociation` field for installed engine builds.
+    pub fn association(&self) -> String {
+        format!("{}.{}", self.major_version, self.minor_version)
+    }
+
+    /// Version with the patch number, e.g. `5.4.2`.
+    pub fn full(&self) -> String {
+        format!(
+            "{}.{}.{}",
+            self.major_version, self.minor_version, self.patch_version
+        )
+    }
+}
+
+/// Asks the user a yes/no question, answering no when not run from a terminal.
+fn confirm(question: &str) -> bool {
+    use std::io::IsTerminal;
+    if !io::stdin().is_terminal() {
+        return false;
+    }
+    print!("{question} [y/N] ");
+    let _ = io::stdout().flush();
+    let mut answer = String::new();
+    if io::stdin().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim(), "y" | "Y" | "yes")
+}
+
+fn project_name(project_path: &Path) -> String {
+    project_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Finds the file with the extension in the directory or the closest of its parents.
+///
+/// The directory can also be the path to the file itself.
 fn find_file_by_extension(
     dir: &Option<PathBuf>,
     extension: &str,
 ) -> Result<PathBuf, Box<dyn Error>> {
-    let path = dir.clone().unwrap_or(std::env::current_dir()?);
-    // Read the directory contents
-    let entries = std::fs::read_dir(path)?;
-
-    // Iterate over the directory entries
-    for entry in entries {
-        let Ok(entry) = entry else {
-            continue;
-        };
-        let path = entry.path();
-
-        // Check if the entry is a file with .uproject extension
-        if path.is_file() {
-            if let Some(ext) = path.extension() {
-                if ext == extension {
-                    let path = std::fs::canonicalize(path)?;
-                    return Ok(path);
-                }
-            }
+    let start = dir.clone().unwrap_or(std::env::current_dir()?);
+    if start.is_file() && start.extension().is_some_and(|e| e == extension) {
+        return Ok(std::fs::canonicalize(start)?);
+    }
+    let start = std::fs::canonicalize(&start)
+        .map_err(|e| format!("Failed to open {}: {e}", start.display()))?;
+
+    for dir in start.ancestors() {
+        if let Some(file) = find_file_in_dir(dir, extension)? {
+            return Ok(file);
         }
     }
     Err(Box::new(io::Error::new(
         io::ErrorKind::NotFound,
-        "Could not find file with extension in this dir",
+        format!(
+            "Could not find a .{extension} file in {} or any of its parents",
+            start.display()
+        ),
     )))
 }
 
+/// The only file with the extension directly in the directory.
+fn find_file_in_dir(dir: &Path, extension: &str) -> Result<Option<PathBuf>, Box<dyn Error>> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Ok(None);
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_file() && p.extension().is_some_and(|e| e == extension))
+        .collect();
+    if files.len() > 1 {
+        files.sort();
+        let choices: Vec<String> = files
+            .iter()
+            .filter_map(|f| f.file_name())
+            .map(|f| f.to_string_lossy().into_owned())
+            .collect();
+        return Err(format!(
+            "Found multiple .{extension} files in {}: {}. Pass the path to one of them",
+            dir.display(),
+            choices.join(", ")
+        )
+        .into());
+    }
+    Ok(files.pop())
+}
+
+/// Command running the engine batch file or shell script.
+fn script_command(script: &Path) -> Command {
+    if cfg!(target_os = "windows") {
+        let mut command = Command::new("cmd");
+        command.arg("/C").arg(script);
+        command
+    } else {
+        Command::new(script)
+    }
+}
+
+/// Adds the `-Name="Value"` argument as is, the engine splits its command line on the spaces
+/// outside of quotes and the default quoting would wrap the whole argument.
+#[cfg(windows)]
+fn push_engine_arg<'a>(command: &'a mut Command, name: &str, value: &str) -> &'a mut Command {
+    use std::os::windows::process::CommandExt;
+    command.raw_arg(format!("-{name}=\"{value}\""))
+}
+
+/// Adds the `-Name=Value` argument, the engine quotes the value of arguments with spaces itself.
+#[cfg(not(windows))]
+fn push_engine_arg<'a>(command: &'a mut Command, name: &str, value: &str) -> &'a mut Command {
+    command.arg(format!("-{name}={value}"))
+}
+
 trait CmdHelper {
     fn run_with_async_logs(&mut self, editor: &Editor) -> ExitStatus;
     fn run_in_bg(&mut self);
+    /// Adds the `-Name=Value` engine argument with a value that can contain spaces.
+    fn engine_arg(&mut self, name: &str, value: &str) -> &mut Self;
 }
 impl CmdHelper for Command {
+    fn engine_arg(&mut self, name: &str, value: &str) -> &mut Self {
+        push_engine_arg(self, name, value)
+    }
+
     fn run_with_async_logs(&mut self, editor: &Editor) -> ExitStatus {
         if editor.dry_run {
             cyan_ln_bold!("[DRY_RUN] {:?}", self);
@@ -440,6 +1749,8 @@ impl CmdHelper for Command {
         status
     }
 
+    // The spawned process is meant to outlive uec.
+    #[allow(clippy::zombie_processes)]
     fn run_in_bg(&mut self) {
         self.spawn().unwrap();
     }
@@ -0,0 +1,354 @@
+use clap::ValueEnum;
+use colour::yellow_ln_bold;
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    error::Error,
+    fmt::Write,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    build_rules::{self, Dependency},
+    uplugin,
+    uproject::{self, Module},
+};
+
+/// Engine modules that are only available in editor builds.
+const ENGINE_EDITOR_MODULES: [&str; 10] = [
+    "UnrealEd",
+    "LevelEditor",
+    "PropertyEditor",
+    "EditorSubsystem",
+    "EditorFramework",
+    "EditorStyle",
+    "Blutility",
+    "AssetTools",
+    "ContentBrowser",
+    "Kismet",
+];
+
+#[derive(Debug, Clone, Copy, Default, ValueEnum)]
+pub enum GraphFormat {
+    /// Graphviz DOT.
+    #[default]
+    Dot,
+    Json,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ModuleNode {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub module_type: String,
+    /// Name of the plugin that contains the module, `None` for the project modules.
+    pub plugin: Option<String>,
+    pub build_cs: PathBuf,
+    pub dependencies: Vec<DependencyEdge>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DependencyEdge {
+    pub name: String,
+    pub public: bool,
+    pub editor_only: bool,
+}
+
+impl From<Dependency> for DependencyEdge {
+    fn from(d: Dependency) -> Self {
+        DependencyEdge {
+            name: d.name,
+            public: d.public,
+            editor_only: d.editor_only,
+        }
+    }
+}
+
+/// Dependency of a module that ends up in game builds on a module that only exists in the editor.
+#[derive(Debug, Serialize)]
+pub struct IllegalDependency {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ModuleGraph {
+    pub modules: Vec<ModuleNode>,
+    pub cycles: Vec<Vec<String>>,
+    pub illegal: Vec<IllegalDependency>,
+}
+
+fn is_editor_type(module_type: &str) -> bool {
+    module_type.starts_with("Editor") || module_type == "UncookedOnly"
+}
+
+fn read_node(module: &Module, source_dir: &Path, plugin: Option<&str>) -> ModuleNode {
+    let build_cs = source_dir
+        .join(&module.Name)
+        .join(format!("{}.Build.cs", module.Name));
+    let dependencies = std::fs::read_to_string(&build_cs)
+        .map(|content| build_rules::parse_dependencies(&content))
+        .unwrap_or_default();
+    ModuleNode {
+        name: module.Name.clone(),
+        module_type: module.Type.clone(),
+        plugin: plugin.map(str::to_owned),
+        build_cs,
+        dependencies: merge_dependencies(dependencies),
+    }
+}
+
+/// Merges the dependencies listed more than once, for example both as public and private,
+/// into a single edge that is public when any of them is.
+fn merge_dependencies(dependencies: Vec<Dependency>) -> Vec<DependencyEdge> {
+    let mut edges: Vec<DependencyEdge> = vec![];
+    for dependency in dependencies {
+        match edges.iter_mut().find(|e| e.name == dependency.name) {
+            Some(edge) => {
+                edge.public |= dependency.public;
+                edge.editor_only &= dependency.editor_only;
+            }
+            None => edges.push(dependency.into()),
+        }
+    }
+    edges
+}
+
+/// Reads the dependencies of the project modules and the modules of the project plugins.
+pub fn build_module_graph(project_path: &Path) -> Result<ModuleGraph, Box<dyn Error>> {
+    let project_dir = project_path.parent().ok_or("Invalid project path")?;
+    let project = uproject::read_config(project_path)?;
+    let mut modules: Vec<ModuleNode> = project
+        .Modules
+        .iter()
+        .map(|m| read_node(m, &project_dir.join("Source"), None))
+        .collect();
+
+    let plugins: BTreeMap<_, _> = uplugin::find_plugins(&project_dir.join("Plugins"))
+        .into_iter()
+        .collect();
+    for (name, descriptor_path) in plugins {
+        let descriptor = match uplugin::read_config(&descriptor_path) {
+            Ok(descriptor) => descriptor,
+            Err(e) => {
+                yellow_ln_bold!("Skipping {}: {}", descriptor_path.display(), e);
+                continue;
+            }
+        };
+        let source_dir = descriptor_path
+            .parent()
+            .ok_or("Invalid plugin path")?
+            .join("Source");
+        modules.extend(
+            descriptor
+                .Modules
+                .iter()
+                .map(|m| read_node(m, &source_dir, Some(&name))),
+        );
+    }
+
+    let cycles = find_cycles(&modules);
+    let illegal = find_illegal_dependencies(&modules);
+    Ok(ModuleGraph {
+        modules,
+        cycles,
+        illegal,
+    })
+}
+
+fn find_illegal_dependencies(modules: &[ModuleNode]) -> Vec<IllegalDependency> {
+    let types: HashMap<&str, &str> = modules
+        .iter()
+        .map(|m| (m.name.as_str(), m.module_type.as_str()))
+        .collect();
+    let mut illegal = vec![];
+    for module in modules.iter().filter(|m| !is_editor_type(&m.module_type)) {
+        for dependency in module.dependencies.iter().filter(|d| !d.editor_only) {
+            let is_editor_dependency = match types.get(dependency.name.as_str()) {
+                Some(t) => is_editor_type(t),
+                None => ENGINE_EDITOR_MODULES.contains(&dependency.name.as_str()),
+            };
+            if is_editor_dependency {
+                illegal.push(IllegalDependency {
+                    from: module.name.clone(),
+                    to: dependency.name.clone(),
+                });
+            }
+        }
+    }
+    illegal
+}
+
+/// Finds the elementary cycles between the project modules, each one is reported once,
+/// starting from its module listed first.
+fn find_cycles(modules: &[ModuleNode]) -> Vec<Vec<String>> {
+    let index: HashMap<&str, usize> = modules
+        .iter()
+        .enumerate()
+        .map(|(i, m)| (m.name.as_str(), i))
+        .collect();
+    let edges: Vec<Vec<usize>> = modules
+        .iter()
+        .map(|m| {
+            m.dependencies
+                .iter()
+                .filter_map(|d| index.get(d.name.as_str()).copied())
+                .collect()
+        })
+        .collect();
+
+    let mut cycles = vec![];
+    let mut on_path = vec![false; modules.len()];
+    for start in 0..modules.len() {
+        let mut path = vec![start];
+        // Only the modules after the start are visited, so a cycle is found from its first module only.
+        visit(
+            start,
+            start,
+            &edges,
+            &mut path,
+            &mut on_path,
+            &mut |cycle| {
+                cycles.push(cycle.iter().map(|i| modules[*i].name.clone()).collect());
+            },
+        );
+    }
+    cycles
+}
+
+fn visit(
+    start: usize,
+    node: usize,
+    edges: &[Vec<usize>],
+    path: &mut Vec<usize>,
+    on_path: &mut [bool],
+    on_cycle: &mut impl FnMut(&[usize]),
+) {
+    on_path[node] = true;
+    for &next in &edges[node] {
+        if next == start {
+            on_cycle(path);
+        } else if next > start && !on_path[next] {
+            path.push(next);
+            visit(start, next, edges, path, on_path, on_cycle);
+            path.pop();
+        }
+    }
+    on_path[node] = false;
+}
+
+impl ModuleGraph {
+    pub fn to_dot(&self, internal_only: bool) -> String {
+        let internal: HashSet<&str> = self.modules.iter().map(|m| m.name.as_str()).collect();
+        let illegal: HashSet<(&str, &str)> = self
+            .illegal
+            .iter()
+            .map(|d| (d.from.as_str(), d.to.as_str()))
+            .collect();
+        let mut dot = String::from("digraph modules {\n    rankdir=LR;\n    node [shape=box];\n");
+        for module in &self.modules {
+            let color = if is_editor_type(&module.module_type) {
+                "lightblue"
+            } else {
+                "lightgoldenrod"
+            };
+            let _ = writeln!(
+                dot,
+                "    \"{}\" [style=filled, fillcolor={color}, tooltip=\"{}\"];",
+                module.name, module.module_type
+            );
+        }
+        for module in &self.modules {
+            for dependency in &module.dependencies {
+                let is_internal = internal.contains(dependency.name.as_str());
+                if internal_only && !is_internal {
+                    continue;
+                }
+                let mut attributes = vec![];
+                if !dependency.public {
+                    attributes.push("style=dashed");
+                }
+                if !is_internal {
+                    attributes.push("color=gray");
+                }
+                if illegal.contains(&(module.name.as_str(), dependency.name.as_str())) {
+                    attributes.push("color=red");
+                }
+                let _ = writeln!(
+                    dot,
+                    "    \"{}\" -> \"{}\" [{}];",
+                    module.name,
+                    dependency.name,
+                    attributes.join(", ")
+                );
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn module(name: &str, dependencies: &[&str]) -> ModuleNode {
+        ModuleNode {
+            name: name.to_owned(),
+            module_type: "Runtime".to_owned(),
+            plugin: None,
+            build_cs: PathBuf::from(format!("{name}.Build.cs")),
+            dependencies: dependencies
+                .iter()
+                .map(|d| DependencyEdge {
+                    name: (*d).to_owned(),
+                    public: false,
+                    editor_only: false,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn finds_cycles_sharing_modules() {
+        let modules = [
+            module("A", &["B", "C"]),
+            module("B", &["C"]),
+            module("C", &["A", "Core"]),
+        ];
+        assert_eq!(
+            find_cycles(&modules),
+            vec![vec!["A", "B", "C"], vec!["A", "C"]]
+        );
+    }
+
+    #[test]
+    fn merges_public_and_private_dependency() {
+        let dependency = |public| Dependency {
+            name: "B".to_owned(),
+            public,
+            editor_only: false,
+        };
+        let edges = merge_dependencies(vec![dependency(false), dependency(true)]);
+        assert_eq!(edges.len(), 1);
+        assert!(edges[0].public);
+        let modules = [
+            ModuleNode {
+                dependencies: edges,
+                ..module("A", &[])
+            },
+            module("B", &["A"]),
+        ];
+        assert_eq!(find_cycles(&modules), vec![vec!["A", "B"]]);
+    }
+
+    #[test]
+    fn finds_no_cycles_in_acyclic_graph() {
+        let modules = [
+            module("A", &["B", "C"]),
+            module("B", &["C"]),
+            module("C", &[]),
+        ];
+        assert!(find_cycles(&modules).is_empty());
+    }
+}
@@ -0,0 +1,220 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::Path;
+
+/// IDE the project files are generated for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Ide {
+    Vscode,
+    /// Rider in the `.uproject` mode, which reads the project directly without generated project files.
+    Rider,
+    /// The newest installed Visual Studio.
+    Vs,
+    Cmake,
+    Make,
+    Xcode,
+}
+
+impl Ide {
+    /// Value of the UnrealBuildTool `-ProjectFileFormat` argument, `None` when the IDE opens the `.uproject` file.
+    pub fn project_file_format(&self) -> Option<&'static str> {
+        match self {
+            Ide::Vscode => Some("VisualStudioCode"),
+            Ide::Rider => None,
+            Ide::Vs => Some("VisualStudio"),
+            Ide::Cmake => Some("CMake"),
+            Ide::Make => Some("Make"),
+            Ide::Xcode => Some("XCode"),
+        }
+    }
+}
+
+/// Problem matchers for the MSVC style diagnostics highlighted by uec and the clang ones.
+fn problem_matchers() -> Value {
+    json!([
+        {
+            "owner": "cpp",
+            "fileLocation": "absolute",
+            "pattern": {
+                "regexp": r"^(.*)\((\d+)(?:,(\d+))?\)\s*:\s+(warning|error)\s*(\w*):\s+(.*)$",
+                "file": 1,
+                "line": 2,
+                "column": 3,
+                "severity": 4,
+                "code": 5,
+                "message": 6
+            }
+        },
+        {
+            "owner": "cpp",
+            "fileLocation": "absolute",
+            "pattern": {
+                "regexp": r"^(.*):(\d+):(\d+):\s+(warning|error):\s+(.*)$",
+                "file": 1,
+                "line": 2,
+                "column": 3,
+                "severity": 4,
+                "message": 5
+            }
+        }
+    ])
+}
+
+/// VS Code tasks running the uec commands for the project.
+pub fn vscode_tasks() -> Vec<Value> {
+    let task = |label: &str, command: &str, is_default: bool| {
+        json!({
+            "label": label,
+            "type": "process",
+            "command": "uec",
+            "args": [command, "${workspaceFolder}"],
+            "group": { "kind": "build", "isDefault": is_default },
+            "problemMatcher": problem_matchers()
+        })
+    };
+    vec![
+        task("uec: editor-project", "editor-project", true),
+        task("uec: build", "build", false),
+    ]
+}
+
+/// VS Code debugger configurations for the editor, standalone game and server.
+pub fn vscode_launch_configurations(editor: &Path, uproject: &Path) -> Vec<Value> {
+    let configuration = |name: &str, extra_args: &[&str]| {
+        let mut args = vec![uproject.display().to_string()];
+        args.extend(extra_args.iter().map(|a| a.to_string()));
+        let mut configuration = json!({
+            "name": name,
+            "request": "launch",
+            "program": editor.display().to_string(),
+            "args": args,
+            "cwd": "${workspaceFolder}",
+            "stopAtEntry": false
+        });
+        if cfg!(target_os = "windows") {
+            configuration["type"] = json!("cppvsdbg");
+        } else {
+            configuration["type"] = json!("cppdbg");
+            configuration["MIMode"] = json!(if cfg!(target_os = "macos") {
+                "lldb"
+            } else {
+                "gdb"
+            });
+        }
+        configuration
+    };
+    vec![
+        configuration("uec: Editor", &[]),
+        configuration("uec: Standalone Game", &["-game", "-log"]),
+        configuration("uec: Server", &["-server", "-log"]),
+    ]
+}
+
+/// Removes the comments and trailing commas VS Code allows in its JSON files.
+fn strip_jsonc(content: &str) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut pending_comma = None;
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                pending_comma = None;
+                out.push(c);
+                while let Some(c) = chars.next() {
+                    out.push(c);
+                    match c {
+                        '\\' => out.extend(chars.next()),
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => while chars.next_if(|c| *c != '\n').is_some() {},
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut last = ' ';
+                for c in chars.by_ref() {
+                    if last == '*' && c == '/' {
+                        break;
+                    }
+                    last = c;
+                }
+            }
+            ',' => {
+                pending_comma = Some(out.len());
+                out.push(c);
+            }
+            ']' | '}' => {
+                if let Some(comma) = pending_comma.take() {
+                    out.remove(comma);
+                }
+                out.push(c);
+            }
+            c if c.is_whitespace() => out.push(c),
+            c => {
+                pending_comma = None;
+                out.push(c);
+            }
+        }
+    }
+    out
+}
+
+/// Adds the entries to the array of the existing VS Code file content,
+/// replacing the ones with the same `id_field` value and keeping the others.
+pub fn merge_vscode_entries(
+    existing: Option<&str>,
+    version: &str,
+    array: &str,
+    id_field: &str,
+    entries: Vec<Value>,
+) -> Result<String, serde_json::Error> {
+    let mut file = match existing {
+        Some(content) => serde_json::from_str(&strip_jsonc(content))?,
+        None => json!({ "version": version }),
+    };
+    if !file[array].is_array() {
+        file[array] = json!([]);
+    }
+    let list = file[array].as_array_mut().unwrap();
+    for entry in entries {
+        match list.iter_mut().find(|e| e[id_field] == entry[id_field]) {
+            Some(old) => *old = entry,
+            None => list.push(entry),
+        }
+    }
+    let mut content = serde_json::to_string_pretty(&file)?;
+    content.push('\n');
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merges_entries_into_file_with_comments() {
+        let existing = r#"{
+    // Tasks of the team
+    "version": "2.0.0",
+    "tasks": [
+        /* kept */ { "label": "mine", "url": "http://example.com//x" },
+        { "label": "uec: build", "command": "old" },
+    ],
+}"#;
+        let content = merge_vscode_entries(
+            Some(existing),
+            "2.0.0",
+            "tasks",
+            "label",
+            vec![json!({ "label": "uec: build", "command": "new" })],
+        )
+        .unwrap();
+        let file: Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(file["tasks"][0]["url"], "http://example.com//x");
+        assert_eq!(file["tasks"][1]["command"], "new");
+        assert_eq!(file["tasks"].as_array().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,377 @@
+//! Reader and writer of the Unreal `.ini` config files.
+//!
+//! Edits are done in place on the original lines, so comments and ordering of the file are kept.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+#[cfg(target_os = "windows")]
+pub const INI_PLATFORM: &str = "Windows";
+#[cfg(target_os = "linux")]
+pub const INI_PLATFORM: &str = "Linux";
+#[cfg(target_os = "macos")]
+pub const INI_PLATFORM: &str = "Mac";
+
+/// Operation done on the key, selected by the first character of the line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    /// `Key=Value` replaces all values.
+    Set,
+    /// `+Key=Value` adds the value when it is not present yet.
+    AddUnique,
+    /// `.Key=Value` adds the value even when it is already present.
+    Add,
+    /// `-Key=Value` removes the value.
+    Remove,
+    /// `!Key=` clears all values.
+    Clear,
+}
+
+#[derive(Debug, Clone)]
+pub struct Entry<'a> {
+    pub section: &'a str,
+    pub op: Op,
+    pub key: &'a str,
+    pub value: &'a str,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Ini {
+    lines: Vec<String>,
+    newline: &'static str,
+    trailing_newline: bool,
+    /// Whether the file starts with the UTF-8 byte order mark, the editor saves some files with it.
+    bom: bool,
+}
+
+fn parse_section(line: &str) -> Option<&str> {
+    let line = line.trim();
+    line.strip_prefix('[')?.strip_suffix(']')
+}
+
+fn parse_entry(line: &str) -> Option<(Op, &str, &str)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with(';') || line.starts_with('#') || line.starts_with('[') {
+        return None;
+    }
+    let (op, rest) = match line.chars().next()? {
+        '+' => (Op::AddUnique, &line[1..]),
+        '.' => (Op::Add, &line[1..]),
+        '-' => (Op::Remove, &line[1..]),
+        '!' => (Op::Clear, &line[1..]),
+        _ => (Op::Set, line),
+    };
+    let (key, value) = rest.split_once('=').unwrap_or((rest, ""));
+    Some((op, key.trim(), value.trim()))
+}
+
+impl Ini {
+    pub fn parse(text: &str) -> Self {
+        let bom = text.starts_with('\u{feff}');
+        let text = text.trim_start_matches('\u{feff}');
+        let newline = if text.contains("\r\n") { "\r\n" } else { "\n" };
+        Ini {
+            lines: text.lines().map(str::to_owned).collect(),
+            newline,
+            trailing_newline: text.is_empty() || text.ends_with('\n'),
+            bom,
+        }
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(Self::parse(&std::fs::read_to_string(path)?))
+    }
+
+    pub fn entries(&self) -> Vec<Entry<'_>> {
+        let mut section = "";
+        let mut entries = vec![];
+        for (line, text) in self.lines.iter().enumerate() {
+            if let Some(name) = parse_section(text) {
+                section = name;
+                continue;
+            }
+            if let Some((op, key, value)) = parse_entry(text) {
+                entries.push(Entry {
+                    section,
+                    op,
+                    key,
+                    value,
+                    line,
+                });
+            }
+        }
+        entries
+    }
+
+    /// Applies the operations from this file on the values of the key.
+    pub fn apply(&self, section: &str, key: &str, values: &mut Vec<String>) {
+        // Consecutive `Key=` lines build an array, a `Key=` line after other operations starts over.
+        let mut in_set_run = false;
+        for entry in self.entries() {
+            if !entry.section.eq_ignore_ascii_case(section) || !entry.key.eq_ignore_ascii_case(key)
+            {
+                continue;
+            }
+            match entry.op {
+                Op::Set => {
+                    if !in_set_run {
+                        values.clear();
+                    }
+                    values.push(entry.value.to_owned());
+                }
+                Op::AddUnique => {
+                    if !values.iter().any(|v| v == entry.value) {
+                        values.push(entry.value.to_owned());
+                    }
+                }
+                Op::Add => values.push(entry.value.to_owned()),
+                Op::Remove => values.retain(|v| v != entry.value),
+                Op::Clear => values.clear(),
+            }
+            in_set_run = entry.op == Op::Set;
+        }
+    }
+
+    pub fn get(&self, section: &str, key: &str) -> Vec<String> {
+        let mut values = vec![];
+        self.apply(section, key, &mut values);
+        values
+    }
+
+    /// Index of the line after which new entries of the section should go, skipping trailing empty lines.
+    fn section_end(&self, section: &str) -> Option<usize> {
+        let start = self
+            .lines
+            .iter()
+            .position(|l| parse_section(l).is_some_and(|s| s.eq_ignore_ascii_case(section)))?;
+        let mut end = start;
+        for (i, line) in self.lines.iter().enumerate().skip(start + 1) {
+            if parse_section(line).is_some() {
+                break;
+            }
+            if !line.trim().is_empty() {
+                end = i;
+            }
+        }
+        Some(end)
+    }
+
+    fn insert_in_section(&mut self, section: &str, line: String) {
+        match self.section_end(section) {
+            Some(end) => self.lines.insert(end + 1, line),
+            None => {
+                if self.lines.last().is_some_and(|l| !l.trim().is_empty()) {
+                    self.lines.push(String::new());
+                }
+                self.lines.push(format!("[{section}]"));
+                self.lines.push(line);
+            }
+        }
+    }
+
+    /// Sets the value of the key, replacing the first `Key=` line in place and removing the other lines
+    /// of the key from the section, including the `+Key=` array operations, so the file resolves to the value.
+    pub fn set(&mut self, section: &str, key: &str, value: &str) {
+        let entries = self.entries();
+        let existing: Vec<&Entry> = entries
+            .iter()
+            .filter(|e| e.section.eq_ignore_ascii_case(section) && e.key.eq_ignore_ascii_case(key))
+            .collect();
+        let Some(first) = existing
+            .iter()
+            .find(|e| e.op == Op::Set)
+            .or(existing.first())
+            .map(|e| e.line)
+        else {
+            self.insert_in_section(section, format!("{key}={value}"));
+            return;
+        };
+        let mut removed: Vec<usize> = existing
+            .iter()
+            .map(|e| e.line)
+            .filter(|line| *line != first)
+            .collect();
+        removed.reverse();
+        let indent_len = self.lines[first].len() - self.lines[first].trim_start().len();
+        let indent = self.lines[first][..indent_len].to_owned();
+        self.lines[first] = format!("{indent}{key}={value}");
+        for line in removed {
+            self.lines.remove(line);
+        }
+    }
+
+    /// Adds a `+Key=Value` line unless the same one is already there.
+    pub fn add_unique(&mut self, section: &str, key: &str, value: &str) {
+        let exists = self.entries().iter().any(|e| {
+            e.op == Op::AddUnique
+                && e.section.eq_ignore_ascii_case(section)
+                && e.key.eq_ignore_ascii_case(key)
+                && e.value == value
+        });
+        if !exists {
+            self.insert_in_section(section, format!("+{key}={value}"));
+        }
+    }
+}
+
+impl std::fmt::Display for Ini {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.bom {
+            f.write_str("\u{feff}")?;
+        }
+        let mut text = self.lines.join(self.newline);
+        if self.trailing_newline && !self.lines.is_empty() {
+            text.push_str(self.newline);
+        }
+        f.write_str(&text)
+    }
+}
+
+/// Config category from the argument, accepting `Engine`, `DefaultEngine` and `DefaultEngine.ini`.
+pub fn category(file: &str) -> &str {
+    let file = file.strip_suffix(".ini").unwrap_or(file);
+    file.strip_prefix("Default").unwrap_or(file)
+}
+
+/// Files that are merged to get the effective config values, in order of loading.
+///
+/// Engine base → engine platform base → project default → platform overrides → saved,
+/// the same hierarchy the engine uses.
+pub fn layer_paths(engine_dir: &Path, project_dir: &Path, category: &str) -> Vec<PathBuf> {
+    let engine_config = engine_dir.join("Engine").join("Config");
+    let project_config = project_dir.join("Config");
+    let saved_config = project_dir.join("Saved").join("Config");
+    vec![
+        engine_config.join("Base.ini"),
+        engine_config.join(format!("Base{category}.ini")),
+        engine_config
+            .join(INI_PLATFORM)
+            .join(format!("Base{INI_PLATFORM}{category}.ini")),
+        project_config.join(format!("Default{category}.ini")),
+        engine_config
+            .join(INI_PLATFORM)
+            .join(format!("{INI_PLATFORM}{category}.ini")),
+        project_config
+            .join(INI_PLATFORM)
+            .join(format!("{INI_PLATFORM}{category}.ini")),
+        saved_config
+            .join(format!("{INI_PLATFORM}Editor"))
+            .join(format!("{category}.ini")),
+    ]
+}
+
+/// Value of the key after applying all layers, with the files that changed it.
+pub fn effective_value(
+    layers: &[PathBuf],
+    section: &str,
+    key: &str,
+) -> (Vec<String>, Vec<PathBuf>) {
+    let mut values = vec![];
+    let mut sources = vec![];
+    for path in layers {
+        let Ok(ini) = Ini::load(path) else {
+            continue;
+        };
+        let before = values.clone();
+        ini.apply(section, key, &mut values);
+        if before != values {
+            sources.push(path.clone());
+        }
+    }
+    (values, sources)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEFAULT_GAME: &str = "\u{feff}[/Script/EngineSettings.GeneralProjectSettings]\r\n\
+ProjectID=1234\r\n\
+; Version of the game\r\n\
+ProjectVersion=1.0.0\r\n\
+\r\n\
+[/Script/UnrealEd.ProjectPackagingSettings]\r\n\
++MapsToCook=(FilePath=\"/Game/Maps/Menu\")\r\n";
+
+    const SECTION: &str = "/Script/EngineSettings.GeneralProjectSettings";
+    const PACKAGING: &str = "/Script/UnrealEd.ProjectPackagingSettings";
+
+    #[test]
+    fn keeps_unchanged_file_as_is() {
+        assert_eq!(Ini::parse(DEFAULT_GAME).to_string(), DEFAULT_GAME);
+    }
+
+    #[test]
+    fn sets_value_in_place() {
+        let mut ini = Ini::parse(DEFAULT_GAME);
+        ini.set(SECTION, "ProjectVersion", "1.1.0");
+        assert_eq!(
+            ini.to_string(),
+            DEFAULT_GAME.replace("ProjectVersion=1.0.0", "ProjectVersion=1.1.0")
+        );
+        let reparsed = Ini::parse(&ini.to_string());
+        assert_eq!(reparsed.get(SECTION, "projectversion"), ["1.1.0"]);
+    }
+
+    #[test]
+    fn set_replaces_array_operations() {
+        let mut ini = Ini::parse("[S]\n+Key=A\nOther=1\nKey=B\n-Key=C\n\n[T]\n+Key=D\n");
+        ini.set("S", "Key", "E");
+        assert_eq!(ini.to_string(), "[S]\nOther=1\nKey=E\n\n[T]\n+Key=D\n");
+        let mut values = vec!["Base".to_owned()];
+        ini.apply("S", "Key", &mut values);
+        assert_eq!(values, ["E"]);
+
+        let mut ini = Ini::parse("[S]\n+Key=A\n+Key=B\n");
+        ini.set("S", "Key", "C");
+        assert_eq!(ini.to_string(), "[S]\nKey=C\n");
+    }
+
+    #[test]
+    fn adds_values_to_sections() {
+        let mut ini = Ini::parse(DEFAULT_GAME);
+        ini.add_unique(PACKAGING, "MapsToCook", "(FilePath=\"/Game/Maps/Menu\")");
+        assert_eq!(ini.to_string(), DEFAULT_GAME);
+        ini.add_unique(PACKAGING, "MapsToCook", "(FilePath=\"/Game/Maps/Level\")");
+        ini.set("/Script/Engine.GameSession", "MaxPlayers", "8");
+        assert!(ini.to_string().ends_with(
+            "+MapsToCook=(FilePath=\"/Game/Maps/Level\")\r\n\r\n[/Script/Engine.GameSession]\r\nMaxPlayers=8\r\n"
+        ));
+        assert_eq!(ini.get(PACKAGING, "MapsToCook").len(), 2);
+    }
+
+    #[test]
+    fn applies_array_operations() {
+        let base = Ini::parse("[S]\nKey=A\nKey=B\n");
+        let default = Ini::parse("[S]\n-Key=A\n+Key=B\n.Key=B\n+Key=C\n");
+        let mut values = vec![];
+        base.apply("S", "Key", &mut values);
+        assert_eq!(values, ["A", "B"]);
+        default.apply("S", "Key", &mut values);
+        assert_eq!(values, ["B", "B", "C"]);
+        Ini::parse("[S]\n!Key=\nKey=D\n").apply("S", "Key", &mut values);
+        assert_eq!(values, ["D"]);
+    }
+
+    #[test]
+    fn project_default_overrides_engine_platform_base() {
+        let engine = Path::new("Engine").join("Engine").join("Config");
+        let project = Path::new("Game").join("Config");
+        let platform_file = format!("{INI_PLATFORM}Game.ini");
+        let expected = [
+            engine.join("Base.ini"),
+            engine.join("BaseGame.ini"),
+            engine
+                .join(INI_PLATFORM)
+                .join(format!("Base{platform_file}")),
+            project.join("DefaultGame.ini"),
+            engine.join(INI_PLATFORM).join(&platform_file),
+            project.join(INI_PLATFORM).join(&platform_file),
+        ];
+        let layers = layer_paths(Path::new("Engine"), Path::new("Game"), "Game");
+        assert_eq!(layers[..expected.len()], expected);
+    }
+}
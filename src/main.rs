@@ -1,19 +1,39 @@
 use std::{io, path::PathBuf};
 
-use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
 use clap_complete::{
     aot::{Bash, Elvish, Fish, PowerShell, Zsh},
     generate,
 };
 use clap_complete_nushell::Nushell;
-use colour::{cyan_ln_bold, green_ln_bold};
+use colour::{cyan_ln_bold, e_red_ln, green_ln_bold};
+use config::BuildProfile;
 use editor::Editor;
+use graph::GraphFormat;
+use ide::Ide;
+use packaging::{BuildPreset, BuildVariant, PackageOptions};
+use scaffold::{ParentClass, PluginTemplate};
 use serde::{Deserialize, Serialize};
+use targets::{Configuration, TargetType};
+use uproject::{LoadingPhase, ModuleType};
 
+pub mod automation;
+pub mod build_rules;
+pub mod check;
 pub mod config;
 pub mod consts;
+pub mod disk;
 pub mod editor;
+pub mod graph;
+pub mod ide;
+pub mod ini;
+pub mod manifest;
+pub mod packaging;
+pub mod scaffold;
+pub mod targets;
+pub mod uplugin;
 pub mod uproject;
+pub mod workspace;
 
 #[derive(Parser)]
 #[command(version, about, long_about = "Unreal Engine CLI helper tool")]
@@ -33,6 +53,10 @@ pub struct Cli {
     /// Dry run, no command would be run. Instead it will just output what it would run.
     #[clap(long, action)]
     dry_run: bool,
+    /// Path to the `.uproject` file to use.
+    /// When no value is provided it is searched in the given directory and its parents
+    #[arg(long, global = true, value_name = "FILE")]
+    project: Option<PathBuf>,
 }
 
 #[derive(Subcommand)]
@@ -40,22 +64,59 @@ pub enum Commands {
     /// Runs the Unreal editor without an Unreal project.
     Editor,
     /// Builds a Unreal project.
+    #[command(disable_version_flag = true)]
     Build {
         /// Optional path to directory containing the `.uproject` file.
         /// When no value is provided it will use current directory
         path: Option<PathBuf>,
         /// Optional path to directory that game would be build to.
-        /// When no value is provided, it will save to the newly created `CookedBuild` directory created in current directory
+        /// When no value is provided, it will save to the newly created `CookedBuild` directory created in current directory.
+        /// Can contain `{project}`, `{platform}`, `{config}`, `{version}`, `{git_sha}` and `{date}` placeholders
         output: Option<PathBuf>,
         /// Before building the project it will generate it first.
         #[clap(long, action)]
         generate_project: bool,
+        #[command(flatten)]
+        build: BuildArgs,
+        #[command(flatten)]
+        version: VersionArgs,
+        #[command(flatten)]
+        workspace: WorkspaceArgs,
+    },
+    /// Creates a new Unreal project from an engine or custom template.
+    New {
+        /// Name of the new project.
+        name: String,
+        /// Template name from the engine `Templates` directory or the configured template directories,
+        /// or a path to the template directory.
+        #[arg(short, long, default_value = "TP_BlankBP")]
+        template: String,
+        /// Optional path to directory in which the project directory would be created.
+        /// When no value is provided it will use current directory
+        path: Option<PathBuf>,
+        /// After creating the project it will generate project files for it.
+        #[clap(long, action)]
+        generate_project: bool,
+    },
+    /// Adds new source code elements to the project.
+    Add {
+        #[command(subcommand)]
+        command: AddCommands,
     },
     /// Generate a Unreal project.
     GenerateProjectFiles {
         /// Optional path to directory containing the `.uproject` file.
         /// When no value is provided it will use current directory
         path: Option<PathBuf>,
+        /// IDE to generate the project files for, remembered in the project `uec.toml`.
+        /// When no value is provided it will use the remembered one or the platform default
+        #[arg(long, value_enum)]
+        ide: Option<Ide>,
+    },
+    /// Sets up the IDE integration of the project.
+    Ide {
+        #[command(subcommand)]
+        command: IdeCommands,
     },
     /// Builds and run a Unreal editor project.
     EditorProject {
@@ -65,17 +126,167 @@ pub enum Commands {
         /// Before building the project it will generate it first.
         #[clap(long, action)]
         generate_project: bool,
+        /// Name of the editor target to build.
+        /// When no value is provided it will use the only editor target or the one named after the project.
+        #[arg(long)]
+        target: Option<String>,
+    },
+    /// Runs the automation tests in the headless editor.
+    /// Exits with non-zero code when any test failed.
+    Test {
+        /// Tests filter passed to `Automation RunTests`.
+        #[arg(default_value = "Project")]
+        filter: String,
+        /// Optional path to directory containing the `.uproject` file.
+        /// When no value is provided it will use current directory
+        path: Option<PathBuf>,
+        /// Directory the editor exports the report to.
+        /// When no value is provided it will use `Saved/Automation/uec` in the project directory.
+        #[arg(long)]
+        report_dir: Option<PathBuf>,
+        /// Path of the JUnit XML report.
+        /// When no value is provided it will save `junit.xml` in the report directory.
+        #[arg(long)]
+        junit: Option<PathBuf>,
+        #[command(flatten)]
+        workspace: WorkspaceArgs,
+    },
+    /// Generates `compile_commands.json` for clangd in the project directory.
+    CompileDb {
+        /// Optional path to directory containing the `.uproject` file.
+        /// When no value is provided it will use current directory
+        path: Option<PathBuf>,
+        /// Name of the editor target, needed when the project declares more than one.
+        #[arg(long)]
+        target: Option<String>,
+        /// Platform to generate the compile commands for.
+        #[arg(long, default_value = consts::PLATFORM)]
+        platform: String,
+        /// Build configuration to generate the compile commands for.
+        #[arg(long, value_enum, default_value_t)]
+        configuration: Configuration,
+        /// Keep only the files from the project `Source` and `Plugins` directories.
+        #[clap(long, action)]
+        project_only: bool,
+    },
+    /// Runs an editor Python script in the headless editor.
+    /// Exits with non-zero code when the script failed.
+    Python {
+        /// Path to the Python script.
+        script: PathBuf,
+        /// Arguments passed to the script.
+        #[arg(last = true)]
+        args: Vec<String>,
+        /// Enable the `PythonScriptPlugin` without asking when it is not enabled.
+        #[clap(long, action)]
+        enable_plugin: bool,
+        /// Optional path to directory containing the `.uproject` file.
+        /// When no value is provided it will use current directory
+        #[arg(long)]
+        path: Option<PathBuf>,
+    },
+    /// Runs a commandlet in the headless editor.
+    /// Exits with the commandlet exit code.
+    #[command(args_conflicts_with_subcommands = true)]
+    Commandlet {
+        #[command(subcommand)]
+        shortcut: Option<CommandletShortcut>,
+        /// Name of the commandlet, without the `Commandlet` suffix.
+        #[arg(required = true)]
+        name: Option<String>,
+        /// Arguments passed to the commandlet.
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+        /// Optional path to directory containing the `.uproject` file.
+        /// When no value is provided it will use current directory
+        #[arg(long)]
+        path: Option<PathBuf>,
+    },
+    /// Lists the targets declared by the `*.Target.cs` files of the project.
+    Targets {
+        /// Optional path to directory containing the `.uproject` file.
+        /// When no value is provided it will use current directory
+        path: Option<PathBuf>,
+    },
+    /// Checks the project for common problems without building it.
+    /// Exits with non-zero code when any problem was found.
+    Check {
+        /// Optional path to directory containing the `.uproject` file.
+        /// When no value is provided it will use current directory
+        path: Option<PathBuf>,
+        /// Target types that should have a `*.Target.cs` file.
+        /// When no value is provided it requires the editor target for projects with C++ modules.
+        #[arg(long, value_enum, value_delimiter = ',')]
+        targets: Vec<TargetType>,
+        #[command(flatten)]
+        workspace: WorkspaceArgs,
+    },
+    /// Edits the module dependencies in the `*.Build.cs` files.
+    Deps {
+        #[command(subcommand)]
+        command: DepsCommands,
+    },
+    /// Reads and edits the project `.ini` config files.
+    Ini {
+        #[command(subcommand)]
+        command: IniCommands,
+    },
+    /// Exports dependency graphs of the project.
+    Graph {
+        #[command(subcommand)]
+        command: GraphCommands,
     },
     /// Cleans all the intermediate files and directories from project.
     CleanProject {
         /// Optional path to directory containing the `.uproject` file.
         /// When no value is provided it will use current directory
         path: Option<PathBuf>,
+        /// Which generated files to remove, from the project and its plugins.
+        #[arg(long, value_enum, default_value_t)]
+        level: CleanLevel,
+        /// Directories kept with the `all` level, rejected with the other levels.
+        #[arg(long, value_enum, value_delimiter = ',')]
+        keep: Vec<KeepDir>,
+        /// Print the removed paths with their sizes and the total space reclaimed.
+        #[clap(long, action)]
+        report: bool,
+        #[command(flatten)]
+        workspace: WorkspaceArgs,
+    },
+    /// Prints the disk usage of the project and the engine caches, sorted by size.
+    Du {
+        /// Optional path to directory containing the `.uproject` file.
+        /// When no value is provided it will use current directory
+        path: Option<PathBuf>,
+        /// Print the report as JSON.
+        #[clap(long, action)]
+        json: bool,
+    },
+    /// Checks the files of a packaged build against its `uec-manifest.json`.
+    Verify {
+        /// Directory containing the `uec-manifest.json` file.
+        dir: PathBuf,
     },
     /// Sets the default Unreal Engine Path.
     SetEditor { name: PathBuf },
     /// Prints the current command configuration.
     PrintConfig,
+    /// Creates a new plugin in the project or engine `Plugins` directory.
+    NewPlugin {
+        /// Name of the new plugin.
+        name: String,
+        #[arg(short, long, value_enum, default_value_t)]
+        template: PluginTemplate,
+        /// Create the plugin in the engine instead of the project.
+        #[clap(long, action)]
+        engine: bool,
+        /// Enable the plugin in the `.uproject` file.
+        #[clap(long, action)]
+        enable: bool,
+        /// Optional path to directory containing the `.uproject` file.
+        /// When no value is provided it will use current directory
+        path: Option<PathBuf>,
+    },
     /// Builds a Unreal plugin.
     BuildPlugin {
         path: Option<PathBuf>,
@@ -99,6 +310,528 @@ pub enum Commands {
     },
 }
 
+/// Targets, platforms and configurations the project is packaged for.
+#[derive(Args, Debug, Clone)]
+pub struct BuildArgs {
+    /// Name of the profile from the project `uec.toml` providing the options not given here.
+    #[arg(long)]
+    pub profile: Option<String>,
+    /// Name of the game target to build.
+    /// When no value is provided it will use the only game target or the one named after the project.
+    #[arg(long)]
+    pub target: Option<String>,
+    /// Platform to package for, with the `Server` suffix for the dedicated server, e.g. `LinuxServer`.
+    /// When no value is provided it will use the current platform
+    #[arg(long)]
+    pub platform: Option<String>,
+    /// Build configuration to package.
+    /// When no value is provided it will use the one from the preset or `Development`
+    #[arg(long, value_enum)]
+    pub configuration: Option<Configuration>,
+    /// Package every `PLATFORM:CONFIGURATION` combination, each to its own archive subdirectory.
+    /// When no value is provided it will use the `matrix` from the project `uec.toml`
+    #[arg(
+        long,
+        num_args = 0..=1,
+        require_equals = true,
+        value_delimiter = ',',
+        value_name = "PLATFORM:CONFIGURATION"
+    )]
+    pub matrix: Option<Vec<BuildVariant>>,
+    /// Compile the matrix combinations before cooking them, in one step per configuration.
+    #[arg(
+        long,
+        requires = "matrix",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true",
+        value_name = "BOOL"
+    )]
+    pub share_build: Option<bool>,
+    /// Number of the newest builds kept next to the output directory, the older ones are removed.
+    /// Requires `{version}`, `{git_sha}` or `{date}` in the output path
+    #[arg(long, value_name = "N")]
+    pub keep_builds: Option<usize>,
+    #[command(flatten)]
+    pub package: PackageArgs,
+}
+
+impl BuildArgs {
+    pub fn platform(&self) -> &str {
+        self.platform.as_deref().unwrap_or(consts::PLATFORM)
+    }
+
+    /// Fills the options not given on the command line from the profile.
+    pub fn with_profile(&self, profile: &BuildProfile) -> BuildArgs {
+        let mut args = self.clone();
+        args.target = args.target.or(profile.target.clone());
+        args.platform = args.platform.or(profile.platform.clone());
+        args.configuration = args.configuration.or(profile.configuration);
+        args.matrix = args.matrix.or(profile.matrix.clone());
+        args.share_build = args.share_build.or(profile.share_build);
+        args.keep_builds = args.keep_builds.or(profile.keep_builds);
+        let package = &mut args.package;
+        package.preset = package.preset.or(profile.preset);
+        package.iostore = package.iostore.or(profile.iostore);
+        package.compressed = package.compressed.or(profile.compressed);
+        package.prereqs = package.prereqs.or(profile.prereqs);
+        package.distribution = package.distribution.or(profile.distribution);
+        package.iterative_cooking = package.iterative_cooking.or(profile.iterative_cooking);
+        package.skip_build = package.skip_build.or(profile.skip_build);
+        package.skip_cook = package.skip_cook.or(profile.skip_cook);
+        if package.maps.is_empty() {
+            package.maps = profile.maps.clone();
+        }
+        package
+            .uat_args
+            .splice(0..0, profile.uat_args.iter().cloned());
+        args
+    }
+
+    pub fn share_build(&self) -> bool {
+        self.share_build.unwrap_or_default()
+    }
+
+    pub fn configuration(&self) -> Configuration {
+        self.configuration
+            .or(self.package.preset.and_then(|p| p.configuration()))
+            .unwrap_or_default()
+    }
+}
+
+/// Steps and options of `BuildCookRun`, added to the ones of the preset.
+///
+/// The switches accept `=false`, turning off the option enabled by the preset or the profile.
+#[derive(Args, Debug, Default, Clone)]
+pub struct PackageArgs {
+    /// Set of options to start from.
+    #[arg(long, value_enum)]
+    pub preset: Option<BuildPreset>,
+    /// Package into I/O store containers.
+    #[arg(
+        long,
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true",
+        value_name = "BOOL"
+    )]
+    pub iostore: Option<bool>,
+    /// Compress the packaged files.
+    #[arg(
+        long,
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true",
+        value_name = "BOOL"
+    )]
+    pub compressed: Option<bool>,
+    /// Include the installer of the prerequisites.
+    #[arg(
+        long,
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true",
+        value_name = "BOOL"
+    )]
+    pub prereqs: Option<bool>,
+    /// Package for distribution, for example to a store.
+    #[arg(
+        long,
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true",
+        value_name = "BOOL"
+    )]
+    pub distribution: Option<bool>,
+    /// Cook only the assets changed since the previous cook.
+    #[arg(
+        long,
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true",
+        value_name = "BOOL"
+    )]
+    pub iterative_cooking: Option<bool>,
+    /// Use the binaries from the previous build.
+    #[arg(
+        long,
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true",
+        value_name = "BOOL"
+    )]
+    pub skip_build: Option<bool>,
+    /// Use the content from the previous cook.
+    #[arg(
+        long,
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true",
+        value_name = "BOOL"
+    )]
+    pub skip_cook: Option<bool>,
+    /// Maps to cook.
+    /// When no value is provided it will cook the maps from the project settings
+    #[arg(long, value_delimiter = ',')]
+    pub maps: Vec<String>,
+    /// Additional arguments passed to `BuildCookRun`, after `--`.
+    #[arg(last = true)]
+    pub uat_args: Vec<String>,
+}
+
+impl PackageArgs {
+    pub fn options(&self) -> PackageOptions {
+        let mut options = self
+            .preset
+            .map(PackageOptions::from_preset)
+            .unwrap_or_default();
+        let switches = [
+            (&mut options.iostore, self.iostore),
+            (&mut options.compressed, self.compressed),
+            (&mut options.prereqs, self.prereqs),
+            (&mut options.distribution, self.distribution),
+            (&mut options.iterative_cooking, self.iterative_cooking),
+            (&mut options.skip_build, self.skip_build),
+            (&mut options.skip_cook, self.skip_cook),
+        ];
+        for (option, switch) in switches {
+            if let Some(switch) = switch {
+                *option = switch;
+            }
+        }
+        options.maps.extend(self.maps.iter().cloned());
+        options.extra_args.extend(self.uat_args.iter().cloned());
+        options
+    }
+}
+
+/// Version written into `DefaultGame.ini` before packaging the project.
+#[derive(Args, Debug, Default)]
+pub struct VersionArgs {
+    /// Semantic version written as `ProjectVersion` of the project settings.
+    #[arg(long = "version", value_name = "SEMVER", value_parser = parse_semver)]
+    pub project_version: Option<String>,
+    /// Write the current git commit as `BuildId` of the project settings.
+    #[clap(long, action)]
+    pub stamp_git: bool,
+    /// Restore the original `DefaultGame.ini` after the build.
+    #[clap(long, action)]
+    pub restore_ini: bool,
+}
+
+impl VersionArgs {
+    pub fn is_set(&self) -> bool {
+        self.project_version.is_some() || self.stamp_git
+    }
+}
+
+fn parse_semver(value: &str) -> Result<String, String> {
+    let core = value.split(['-', '+']).next().unwrap_or_default();
+    let parts: Vec<&str> = core.split('.').collect();
+    let is_valid = parts.len() == 3
+        && parts
+            .iter()
+            .all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()));
+    if is_valid {
+        Ok(value.to_owned())
+    } else {
+        Err(format!("`{value}` is not a MAJOR.MINOR.PATCH version"))
+    }
+}
+
+#[derive(Args, Debug, Default)]
+pub struct WorkspaceArgs {
+    /// Run for all projects of the `uec-workspace.toml` workspace,
+    /// found in the given directory or its parents.
+    #[arg(long, visible_alias = "all")]
+    workspace: bool,
+    /// Run for the workspace projects at the same time.
+    #[arg(long, requires = "workspace")]
+    parallel: bool,
+}
+
+/// Runs the command for the project, or for every workspace project printing the combined summary.
+///
+/// The command gets the project path and, in the workspace, the name of the project.
+fn for_each_project<F>(
+    cli: &Cli,
+    path: &Option<PathBuf>,
+    args: &WorkspaceArgs,
+    command: F,
+) -> Result<bool, Box<dyn std::error::Error>>
+where
+    F: Fn(&Option<PathBuf>, Option<&str>) -> bool + Sync,
+{
+    if !args.workspace {
+        return Ok(command(path, None));
+    }
+    if cli.project.is_some() {
+        return Err("`--project` can not be used with `--workspace`".into());
+    }
+    let dir = match path {
+        Some(path) => path.clone(),
+        None => std::env::current_dir()?,
+    };
+    let members = workspace::read_members(&workspace::find_workspace(&dir)?)?;
+    let results = workspace::run(&members, args.parallel, |member| {
+        command(&Some(member.path.clone()), Some(&member.name))
+    });
+    workspace::print_summary(&results, |passed, total| {
+        format!("{passed} passed, {} failed", total - passed)
+    });
+    Ok(results.iter().all(|r| r.passed))
+}
+
+/// Exits with non-zero code when the command failed for any project.
+fn exit_on_failure(result: Result<bool, Box<dyn std::error::Error>>) {
+    match result {
+        Ok(true) => {}
+        Ok(false) => std::process::exit(1),
+        Err(e) => {
+            eprintln!("Failed to run the command, reason: {:#?}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum CleanLevel {
+    /// `Intermediate` directories.
+    Intermediate,
+    /// `Intermediate` and `Binaries` directories.
+    Binaries,
+    /// `Intermediate` directories, the `.sln` file and the `Build`, `PackagedProject`, `Saved`
+    /// and `DerivedDataCache` directories, `Binaries` are kept.
+    #[default]
+    All,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum KeepDir {
+    /// `DerivedDataCache` directory.
+    Ddc,
+    /// `Saved` directory.
+    Saved,
+}
+
+#[derive(Subcommand)]
+pub enum IdeCommands {
+    /// Writes `.vscode/tasks.json` running uec and `.vscode/launch.json` debugging the editor, game and server.
+    Vscode {
+        /// Optional path to directory containing the `.uproject` file.
+        /// When no value is provided it will use current directory
+        path: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CommandletShortcut {
+    /// Resaves the packages, upgrading them to the current engine version.
+    ResavePackages {
+        /// Resave only the packages from the project, skipping the engine ones.
+        #[clap(long, action)]
+        project_only: bool,
+        /// Resave only the packages from the given content folder, for example `/Game/Maps`.
+        #[arg(long)]
+        package_folder: Option<String>,
+        /// Optional path to directory containing the `.uproject` file.
+        /// When no value is provided it will use current directory
+        #[arg(long)]
+        path: Option<PathBuf>,
+    },
+    /// Fixes up the references to redirectors and removes them.
+    FixupRedirects {
+        /// Check out the files from source control before changing them.
+        #[clap(long, action)]
+        auto_checkout: bool,
+        /// Optional path to directory containing the `.uproject` file.
+        /// When no value is provided it will use current directory
+        #[arg(long)]
+        path: Option<PathBuf>,
+    },
+    /// Runs the data validation on all assets.
+    DataValidation {
+        /// Optional path to directory containing the `.uproject` file.
+        /// When no value is provided it will use current directory
+        #[arg(long)]
+        path: Option<PathBuf>,
+    },
+    /// Compiles all blueprints, reporting the ones with errors.
+    CompileAllBlueprints {
+        /// Compile only blueprints deriving from the given class.
+        #[arg(long)]
+        base_class: Option<String>,
+        /// Optional path to directory containing the `.uproject` file.
+        /// When no value is provided it will use current directory
+        #[arg(long)]
+        path: Option<PathBuf>,
+    },
+}
+
+impl CommandletShortcut {
+    /// Commandlet name and its arguments.
+    pub fn expand(&self) -> (&'static str, Vec<String>, &Option<PathBuf>) {
+        match self {
+            CommandletShortcut::ResavePackages {
+                project_only,
+                package_folder,
+                path,
+            } => {
+                let mut args = vec![];
+                if *project_only {
+                    args.push("-ProjectOnly".to_owned());
+                }
+                if let Some(folder) = package_folder {
+                    args.push(format!("-PackageFolder={folder}"));
+                }
+                ("ResavePackages", args, path)
+            }
+            CommandletShortcut::FixupRedirects {
+                auto_checkout,
+                path,
+            } => {
+                let mut args = vec!["-FixupRedirects".to_owned(), "-ProjectOnly".to_owned()];
+                if *auto_checkout {
+                    args.push("-AutoCheckOut".to_owned());
+                }
+                ("ResavePackages", args, path)
+            }
+            CommandletShortcut::DataValidation { path } => ("DataValidation", vec![], path),
+            CommandletShortcut::CompileAllBlueprints { base_class, path } => {
+                let args = base_class
+                    .iter()
+                    .map(|c| format!("-BlueprintBaseClass={c}"))
+                    .collect();
+                ("CompileAllBlueprints", args, path)
+            }
+        }
+    }
+}
+
+#[derive(Subcommand)]
+pub enum AddCommands {
+    /// Creates a new C++ module and registers it in the `.uproject` and `*.Target.cs` files.
+    Module {
+        /// Name of the new module.
+        name: String,
+        /// Type of the module.
+        #[arg(
+            long = "type",
+            value_enum,
+            ignore_case = true,
+            default_value = "runtime"
+        )]
+        module_type: ModuleType,
+        /// Phase at which the module is loaded.
+        #[arg(long, value_enum, ignore_case = true, default_value = "default")]
+        loading_phase: LoadingPhase,
+        /// Optional path to directory containing the `.uproject` file.
+        /// When no value is provided it will use current directory
+        path: Option<PathBuf>,
+    },
+    /// Creates a header and source file pair for a new class in one of the project modules.
+    Class {
+        /// Name of the module listed in the `.uproject` file.
+        module: String,
+        /// Name of the class without the `A`/`U`/`F` prefix.
+        name: String,
+        /// Class the new one derives from.
+        #[arg(long, value_enum, ignore_case = true, default_value = "UObject")]
+        parent: ParentClass,
+        /// Optional path to directory containing the `.uproject` file.
+        /// When no value is provided it will use current directory
+        path: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DepsCommands {
+    /// Adds a dependency to the module, by default to the private ones.
+    Add {
+        /// Name of the project or project plugin module.
+        module: String,
+        /// Name of the module to depend on.
+        dependency: String,
+        #[clap(long, action, conflicts_with = "private")]
+        public: bool,
+        #[clap(long, action)]
+        private: bool,
+        /// Enable the plugin containing the dependency without asking.
+        #[clap(long, action)]
+        enable_plugin: bool,
+        /// Optional path to directory containing the `.uproject` file.
+        /// When no value is provided it will use current directory
+        path: Option<PathBuf>,
+    },
+    /// Removes a dependency from the module.
+    Remove {
+        /// Name of the project or project plugin module.
+        module: String,
+        /// Name of the module to remove from dependencies.
+        dependency: String,
+        /// Optional path to directory containing the `.uproject` file.
+        /// When no value is provided it will use current directory
+        path: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum IniCommands {
+    /// Prints the effective value of the key after merging Base, Default, platform and Saved config files.
+    Get {
+        /// Config file category, for example `Engine`, `Game` or `DefaultInput.ini`.
+        file: String,
+        /// Section name, for example `/Script/EngineSettings.GeneralProjectSettings`.
+        section: String,
+        key: String,
+        /// Print the files that changed the value.
+        #[clap(long, action)]
+        layers: bool,
+        /// Optional path to directory containing the `.uproject` file.
+        /// When no value is provided it will use current directory
+        path: Option<PathBuf>,
+    },
+    /// Sets the value in the project `Default*.ini` file, keeping the rest of the file untouched.
+    ///
+    /// Without `--add` the other lines of the key in the section, including `+Key=` ones, are removed.
+    Set {
+        /// Config file category, for example `Engine`, `Game` or `DefaultInput.ini`.
+        file: String,
+        /// Section name, for example `/Script/EngineSettings.GeneralProjectSettings`.
+        section: String,
+        key: String,
+        value: String,
+        /// Add the value to the array with `+Key=Value` instead of replacing it.
+        #[clap(long, action)]
+        add: bool,
+        /// Write into the current platform config file instead of the `Default` one.
+        #[clap(long, action)]
+        platform: bool,
+        /// Optional path to directory containing the `.uproject` file.
+        /// When no value is provided it will use current directory
+        path: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum GraphCommands {
+    /// Dependencies between the project and project plugins modules read from their `*.Build.cs` files.
+    /// Exits with non-zero code when cycles or game modules depending on editor modules were found.
+    Modules {
+        /// Optional path to directory containing the `.uproject` file.
+        /// When no value is provided it will use current directory
+        path: Option<PathBuf>,
+        #[arg(short, long, value_enum, default_value_t)]
+        format: GraphFormat,
+        /// Save the graph into specified file instead of printing it.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Skip the dependencies on engine modules in DOT output.
+        #[clap(long, action)]
+        internal_only: bool,
+    },
+}
+
 #[derive(Clone, Debug, Default, Copy, Serialize, Deserialize, ValueEnum)]
 pub enum ActionToDo {
     #[default]
@@ -166,28 +899,334 @@ fn main() {
             path,
             output,
             generate_project,
-        } => {
+            build,
+            version,
+            workspace,
+        } => exit_on_failure(for_each_project(&cli, path, workspace, |path, member| {
             if *generate_project {
-                editor.generate_proj_files(path);
+                editor.generate_proj_files(path, None);
             }
-            let _ = editor.build_project(path, output);
-        }
+            // Workspace projects are archived next to each other.
+            let output = match member {
+                Some(member) => output.as_ref().map(|o| o.join(member)),
+                None => output.clone(),
+            };
+            editor
+                .build_project(path, &output, build, version)
+                .inspect_err(|e| eprintln!("Failed to build the project, reason: {:#?}", e))
+                .unwrap_or(false)
+        })),
         Commands::BuildEngine { path } => editor.build_engine_from_source(path),
-        Commands::CleanProject { path } => {
-            let _ = editor
-                .clean_project(path)
-                .inspect_err(|e| eprintln!("Failed to clean up the project, reason: {:#?}", e));
+        Commands::Check {
+            path,
+            targets,
+            workspace,
+        } => exit_on_failure(for_each_project(
+            &cli,
+            path,
+            workspace,
+            |path, _| match editor.check_project(path, targets) {
+                Ok(problems) => {
+                    check::print_problems(&problems);
+                    problems.is_empty()
+                }
+                Err(e) => {
+                    eprintln!("Failed to check the project, reason: {:#?}", e);
+                    false
+                }
+            },
+        )),
+        Commands::Deps { command } => match command {
+            DepsCommands::Add {
+                module,
+                dependency,
+                public,
+                private: _,
+                enable_plugin,
+                path,
+            } => {
+                let _ = editor
+                    .add_dependency(path, module, dependency, *public, *enable_plugin)
+                    .inspect_err(|e| eprintln!("Failed to add the dependency, reason: {:#?}", e));
+            }
+            DepsCommands::Remove {
+                module,
+                dependency,
+                path,
+            } => {
+                let _ = editor
+                    .remove_dependency(path, module, dependency)
+                    .inspect_err(|e| {
+                        eprintln!("Failed to remove the dependency, reason: {:#?}", e)
+                    });
+            }
+        },
+        Commands::Ini { command } => match command {
+            IniCommands::Get {
+                file,
+                section,
+                key,
+                layers,
+                path,
+            } => match editor.ini_get(path, file, section, key) {
+                Ok((values, sources)) => {
+                    for value in values {
+                        println!("{}", value);
+                    }
+                    if *layers {
+                        for source in sources {
+                            cyan_ln_bold!("{}", source.display());
+                        }
+                    }
+                }
+                Err(e) => eprintln!("Failed to read the config, reason: {:#?}", e),
+            },
+            IniCommands::Set {
+                file,
+                section,
+                key,
+                value,
+                add,
+                platform,
+                path,
+            } => {
+                let _ = editor
+                    .ini_set(path, file, section, key, value, *add, *platform)
+                    .inspect_err(|e| eprintln!("Failed to update the config, reason: {:#?}", e));
+            }
+        },
+        Commands::Graph {
+            command:
+                GraphCommands::Modules {
+                    path,
+                    format,
+                    output,
+                    internal_only,
+                },
+        } => {
+            let graph = editor.module_graph(path).unwrap_or_else(|e| {
+                eprintln!("Failed to read the module graph, reason: {:#?}", e);
+                std::process::exit(1);
+            });
+            let text = match format {
+                GraphFormat::Dot => graph.to_dot(*internal_only),
+                GraphFormat::Json => serde_json::to_string_pretty(&graph).unwrap() + "\n",
+            };
+            match output {
+                Some(output) => std::fs::write(output, text).expect("Failed to write the graph"),
+                None => print!("{}", text),
+            }
+            for cycle in &graph.cycles {
+                e_red_ln!("Dependency cycle: {} -> {}", cycle.join(" -> "), cycle[0]);
+            }
+            for dependency in &graph.illegal {
+                e_red_ln!(
+                    "Module {} depends on editor module {}",
+                    dependency.from,
+                    dependency.to
+                );
+            }
+            if !graph.cycles.is_empty() || !graph.illegal.is_empty() {
+                std::process::exit(1);
+            }
+        }
+        Commands::CleanProject {
+            path,
+            level,
+            keep,
+            report,
+            workspace,
+        } => {
+            if !keep.is_empty() && *level != CleanLevel::All {
+                Cli::command()
+                    .error(
+                        clap::error::ErrorKind::ArgumentConflict,
+                        "`--keep` can only be used with `--level all`",
+                    )
+                    .exit();
+            }
+            exit_on_failure(for_each_project(&cli, path, workspace, |path, _| {
+                editor
+                    .clean_project(path, *level, keep, *report)
+                    .inspect_err(|e| eprintln!("Failed to clean up the project, reason: {:#?}", e))
+                    .is_ok()
+            }))
         }
+        Commands::Du { path, json } => match editor.disk_usage(path) {
+            Ok(usage) if *json => match serde_json::to_string_pretty(&usage) {
+                Ok(report) => println!("{report}"),
+                Err(e) => eprintln!("Failed to serialize the disk usage, reason: {:#?}", e),
+            },
+            Ok(usage) => usage.print(),
+            Err(e) => eprintln!("Failed to read the disk usage, reason: {:#?}", e),
+        },
+        Commands::Verify { dir } => match manifest::verify(dir) {
+            Ok(true) => {}
+            Ok(false) => std::process::exit(1),
+            Err(e) => {
+                eprintln!("Failed to verify the build, reason: {:#?}", e);
+                std::process::exit(1);
+            }
+        },
         Commands::EditorProject {
             path,
             generate_project,
+            target,
         } => {
             if *generate_project {
-                editor.generate_proj_files(path);
+                editor.generate_proj_files(path, None);
+            }
+            let _ = editor
+                .build_editor_project(path, target)
+                .inspect_err(|e| eprintln!("Failed to build the editor project, reason: {:#?}", e));
+        }
+        Commands::Test {
+            filter,
+            path,
+            report_dir,
+            junit,
+            workspace,
+        } => exit_on_failure(for_each_project(&cli, path, workspace, |path, member| {
+            // Workspace projects get their own reports.
+            let (report_dir, junit) = match member {
+                Some(member) => (
+                    report_dir.as_ref().map(|d| d.join(member)),
+                    junit
+                        .as_ref()
+                        .map(|j| j.with_file_name(format!("{member}-junit.xml"))),
+                ),
+                None => (report_dir.clone(), junit.clone()),
+            };
+            match editor.run_tests(path, filter, &report_dir, &junit) {
+                Ok(Some(report)) => {
+                    report.print();
+                    !report.has_failures()
+                }
+                Ok(None) => true,
+                Err(e) => {
+                    eprintln!("Failed to run the tests, reason: {:#?}", e);
+                    false
+                }
+            }
+        })),
+        Commands::CompileDb {
+            path,
+            target,
+            platform,
+            configuration,
+            project_only,
+        } => {
+            match editor.generate_compile_db(path, target, platform, *configuration, *project_only)
+            {
+                Ok(database) => green_ln_bold!("Compile commands: {}", database.display()),
+                Err(e) => {
+                    eprintln!("Failed to generate the compile commands, reason: {:#?}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Python {
+            script,
+            args,
+            enable_plugin,
+            path,
+        } => match editor.run_python_script(path, script, args, *enable_plugin) {
+            Ok(true) => {}
+            Ok(false) => {
+                e_red_ln!("Python script {} failed", script.display());
+                std::process::exit(1);
+            }
+            Err(e) => {
+                eprintln!("Failed to run the Python script, reason: {:#?}", e);
+                std::process::exit(1);
+            }
+        },
+        Commands::Commandlet {
+            shortcut,
+            name,
+            args,
+            path,
+        } => {
+            let result = match shortcut {
+                Some(shortcut) => {
+                    let (name, args, path) = shortcut.expand();
+                    editor.run_commandlet(path, name, args)
+                }
+                None => editor.run_commandlet(path, name.as_deref().unwrap_or_default(), args),
+            };
+            match result {
+                Ok(status) if !status.success() => std::process::exit(status.code().unwrap_or(1)),
+                Ok(_) => {}
+                Err(e) => {
+                    eprintln!("Failed to run the commandlet, reason: {:#?}", e);
+                    std::process::exit(1);
+                }
             }
-            editor.build_editor_project(path)
         }
-        Commands::GenerateProjectFiles { path } => editor.generate_proj_files(path),
+        Commands::Targets { path } => match editor.list_targets(path) {
+            Ok(targets) => {
+                for target in targets {
+                    println!(
+                        "{:<32} {:<8} {}",
+                        target.name,
+                        target.target_type.as_str(),
+                        target.path.display()
+                    );
+                }
+            }
+            Err(e) => eprintln!("Failed to read the targets, reason: {:#?}", e),
+        },
+        Commands::New {
+            name,
+            template,
+            path,
+            generate_project,
+        } => {
+            let _ = editor
+                .new_project(name, template, path, *generate_project)
+                .inspect_err(|e| eprintln!("Failed to create the project, reason: {:#?}", e));
+        }
+        Commands::Add { command } => match command {
+            AddCommands::Module {
+                name,
+                module_type,
+                loading_phase,
+                path,
+            } => {
+                let _ = editor
+                    .add_module(path, name, *module_type, *loading_phase)
+                    .inspect_err(|e| eprintln!("Failed to add the module, reason: {:#?}", e));
+            }
+            AddCommands::Class {
+                module,
+                name,
+                parent,
+                path,
+            } => {
+                let _ = editor
+                    .add_class(path, module, name, *parent)
+                    .inspect_err(|e| eprintln!("Failed to add the class, reason: {:#?}", e));
+            }
+        },
+        Commands::Ide { command } => match command {
+            IdeCommands::Vscode { path } => {
+                let _ = editor.setup_vscode(path).inspect_err(|e| {
+                    eprintln!("Failed to set up VS Code, reason: {:#?}", e);
+                });
+            }
+        },
+        Commands::GenerateProjectFiles { path, ide } => editor.generate_proj_files(path, *ide),
+        Commands::NewPlugin {
+            name,
+            template,
+            engine,
+            enable,
+            path,
+        } => {
+            let _ = editor
+                .new_plugin(path, name, *template, *engine, *enable)
+                .inspect_err(|e| eprintln!("Failed to create the plugin, reason: {:#?}", e));
+        }
         Commands::BuildPlugin { path, output } => editor.build_plugin(path, output),
         Commands::UAT { input, path } => {
             let args: Vec<&str> = input.split(' ').collect();
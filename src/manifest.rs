@@ -0,0 +1,238 @@
+//! Manifest listing the files of a packaged build, used to verify its copies.
+
+use colour::{dark_green_ln_bold, e_red_ln};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    error::Error,
+    ffi::OsStr,
+    fs::File,
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::disk;
+
+pub const MANIFEST_FILE: &str = "uec-manifest.json";
+
+/// Build information and the files of the archive directory.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Manifest {
+    pub project: String,
+    pub engine_version: Option<String>,
+    pub platform: String,
+    pub configuration: String,
+    pub git_commit: Option<String>,
+    pub files: Vec<ManifestFile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestFile {
+    /// Path relative to the archive directory, with `/` separators.
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+/// Difference between the manifest and the directory.
+#[derive(Debug)]
+pub enum Mismatch {
+    Missing(String),
+    Changed(String),
+    Unexpected(String),
+}
+
+impl std::fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Mismatch::Missing(path) => write!(f, "missing: {path}"),
+            Mismatch::Changed(path) => write!(f, "changed: {path}"),
+            Mismatch::Unexpected(path) => write!(f, "not in manifest: {path}"),
+        }
+    }
+}
+
+/// Files under the directory, except for the manifest itself, sorted by path.
+///
+/// When `roots` are given, only the files and directories with these names directly in the directory are listed.
+pub fn list_files(dir: &Path, roots: Option<&[String]>) -> io::Result<Vec<ManifestFile>> {
+    let mut paths: Vec<PathBuf> = vec![];
+    // The directory itself can be a link, e.g. `latest`.
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        if roots.is_some_and(|roots| !roots.iter().any(|r| OsStr::new(r) == name)) {
+            continue;
+        }
+        disk::for_each_file(&entry.path(), &mut |path, _| paths.push(path.to_path_buf()));
+    }
+    let mut files = vec![];
+    for path in paths {
+        let relative = relative_path(dir, &path);
+        if relative == MANIFEST_FILE {
+            continue;
+        }
+        files.push(hash_file(&path, relative)?);
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+fn relative_path(dir: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(dir).unwrap_or(path);
+    relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn hash_file(path: &Path, relative: String) -> io::Result<ManifestFile> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let size = io::copy(&mut file, &mut hasher)?;
+    Ok(ManifestFile {
+        path: relative,
+        size,
+        sha256: format!("{:x}", hasher.finalize()),
+    })
+}
+
+impl Manifest {
+    pub fn write(&self, dir: &Path) -> io::Result<()> {
+        std::fs::write(
+            dir.join(MANIFEST_FILE),
+            serde_json::to_string_pretty(self)? + "\n",
+        )
+    }
+
+    pub fn read(dir: &Path) -> Result<Self, Box<dyn Error>> {
+        let path = dir.join(MANIFEST_FILE);
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Compares the files of the directory with the ones listed in the manifest.
+    ///
+    /// Files next to the top level directories of the manifest are not checked.
+    pub fn verify(&self, dir: &Path) -> io::Result<Vec<Mismatch>> {
+        let roots: BTreeSet<String> = self
+            .files
+            .iter()
+            .map(|f| f.path.split('/').next().unwrap_or_default().to_owned())
+            .collect();
+        let roots: Vec<String> = roots.into_iter().collect();
+        let actual: BTreeMap<String, ManifestFile> = list_files(dir, Some(&roots))?
+            .into_iter()
+            .map(|f| (f.path.clone(), f))
+            .collect();
+        let expected: BTreeSet<&str> = self.files.iter().map(|f| f.path.as_str()).collect();
+        let mut mismatches = vec![];
+        for file in &self.files {
+            match actual.get(&file.path) {
+                None => mismatches.push(Mismatch::Missing(file.path.clone())),
+                Some(actual) if actual != file => {
+                    mismatches.push(Mismatch::Changed(file.path.clone()))
+                }
+                Some(_) => {}
+            }
+        }
+        for path in actual.keys() {
+            if !expected.contains(path.as_str()) {
+                mismatches.push(Mismatch::Unexpected(path.clone()));
+            }
+        }
+        Ok(mismatches)
+    }
+}
+
+/// Verifies the directory against its manifest, printing the differences.
+pub fn verify(dir: &Path) -> Result<bool, Box<dyn Error>> {
+    let manifest = Manifest::read(dir)?;
+    let mismatches = manifest.verify(dir)?;
+    if mismatches.is_empty() {
+        dark_green_ln_bold!(
+            "All {} files of {} {} {} match the manifest",
+            manifest.files.len(),
+            manifest.project,
+            manifest.platform,
+            manifest.configuration
+        );
+        return Ok(true);
+    }
+    for mismatch in &mismatches {
+        e_red_ln!("{}", mismatch);
+    }
+    e_red_ln!("Found {} difference(s)", mismatches.len());
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, path: &str, content: &str) {
+        let path = dir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn verifies_files_of_the_platform_directory() {
+        let dir = std::env::temp_dir().join(format!("uec-manifest-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        write(&dir, "Linux/MyGame.sh", "#!/bin/sh");
+        write(&dir, "Linux/MyGame/Content/Paks/MyGame.pak", "pak");
+        write(&dir, "Windows/MyGame.exe", "stale build");
+        write(&dir, "version.json", "{}");
+
+        let roots = ["Linux".to_owned(), "version.json".to_owned()];
+        let manifest = Manifest {
+            project: "MyGame".to_owned(),
+            engine_version: Some("5.4.1".to_owned()),
+            platform: "Linux".to_owned(),
+            configuration: "Shipping".to_owned(),
+            git_commit: None,
+            files: list_files(&dir, Some(&roots)).unwrap(),
+        };
+        let paths: Vec<&str> = manifest.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "Linux/MyGame.sh",
+                "Linux/MyGame/Content/Paks/MyGame.pak",
+                "version.json"
+            ]
+        );
+        assert_eq!(manifest.files[0].size, 9);
+        assert_eq!(
+            manifest.files[0].sha256,
+            "3af71adb278ad4af33c144b78fa1ae708da03b773d98324ae991a7daedb53ca2"
+        );
+        manifest.write(&dir).unwrap();
+        let manifest = Manifest::read(&dir).unwrap();
+        assert!(manifest.verify(&dir).unwrap().is_empty());
+
+        write(&dir, "Windows/MyGame.exe", "other stale build");
+        write(&dir, "Linux/MyGame.sh", "#!/bin/bash");
+        write(&dir, "Linux/Extra.txt", "extra");
+        std::fs::remove_file(dir.join("version.json")).unwrap();
+        let mismatches: Vec<String> = manifest
+            .verify(&dir)
+            .unwrap()
+            .iter()
+            .map(|m| m.to_string())
+            .collect();
+        assert_eq!(
+            mismatches,
+            [
+                "changed: Linux/MyGame.sh",
+                "missing: version.json",
+                "not in manifest: Linux/Extra.txt"
+            ]
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
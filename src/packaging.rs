@@ -0,0 +1,564 @@
+//! Options of the `BuildCookRun` command used to package the project.
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+use crate::{
+    targets::Configuration,
+    workspace::{self, RunResult},
+};
+
+/// Platform and configuration combination the project is packaged for.
+///
+/// Written as `PLATFORM[:CONFIGURATION]`, the `Server` suffix of the platform selects the
+/// dedicated server, e.g. `LinuxServer:Development`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct BuildVariant {
+    pub platform: String,
+    pub configuration: Configuration,
+    pub server: bool,
+}
+
+impl BuildVariant {
+    pub fn new(platform: &str, configuration: Configuration) -> Self {
+        let (platform, server) = match platform.strip_suffix("Server") {
+            Some(platform) if !platform.is_empty() => (platform, true),
+            _ => (platform, false),
+        };
+        BuildVariant {
+            platform: platform.to_owned(),
+            configuration,
+            server,
+        }
+    }
+
+    /// Platform name including the `Server` suffix.
+    pub fn full_platform(&self) -> String {
+        if self.server {
+            format!("{}Server", self.platform)
+        } else {
+            self.platform.clone()
+        }
+    }
+
+    /// Name of the archive subdirectory of the variant.
+    pub fn dir_name(&self) -> String {
+        format!("{}-{}", self.full_platform(), self.configuration.as_str())
+    }
+
+    /// Names of the directory `BuildCookRun -archive` puts the variant in, it depends on the engine version.
+    pub fn archive_dir_names(&self) -> Vec<String> {
+        let platform = match self.platform.as_str() {
+            "Win64" => "Windows",
+            platform => platform,
+        };
+        if self.server {
+            vec![format!("{platform}Server")]
+        } else {
+            vec![platform.to_owned(), format!("{platform}NoEditor")]
+        }
+    }
+
+    /// Platform and configuration arguments of `BuildCookRun`.
+    pub fn uat_args(&self) -> Vec<String> {
+        let mut args = vec![format!("-platform={}", self.platform)];
+        if self.server {
+            args.extend([
+                "-server".to_owned(),
+                "-noclient".to_owned(),
+                format!("-serverplatform={}", self.platform),
+                format!("-serverconfig={}", self.configuration.as_str()),
+            ]);
+        } else {
+            args.push(format!("-clientconfig={}", self.configuration.as_str()));
+        }
+        args
+    }
+}
+
+/// Platform and configuration arguments compiling the variants ahead of cooking them,
+/// one `BuildCookRun` call per configuration so only the requested combinations are built.
+pub fn shared_build_args(variants: &[BuildVariant]) -> Vec<(Configuration, Vec<String>)> {
+    fn join<'a>(values: impl Iterator<Item = &'a str>) -> String {
+        let mut unique: Vec<&str> = vec![];
+        for value in values {
+            if !unique.contains(&value) {
+                unique.push(value);
+            }
+        }
+        unique.join("+")
+    }
+    let mut configurations: Vec<Configuration> = vec![];
+    for variant in variants {
+        if !configurations.contains(&variant.configuration) {
+            configurations.push(variant.configuration);
+        }
+    }
+    configurations
+        .into_iter()
+        .map(|configuration| {
+            let config = configuration.as_str();
+            let in_config = || {
+                variants
+                    .iter()
+                    .filter(move |v| v.configuration == configuration)
+            };
+            let clients = join(
+                in_config()
+                    .filter(|v| !v.server)
+                    .map(|v| v.platform.as_str()),
+            );
+            let servers = join(
+                in_config()
+                    .filter(|v| v.server)
+                    .map(|v| v.platform.as_str()),
+            );
+            let mut args = vec![];
+            if clients.is_empty() {
+                args.push(format!("-platform={servers}"));
+                args.push("-noclient".to_owned());
+            } else {
+                args.push(format!("-platform={clients}"));
+                args.push(format!("-clientconfig={config}"));
+            }
+            if !servers.is_empty() {
+                args.push("-server".to_owned());
+                args.push(format!("-serverplatform={servers}"));
+                args.push(format!("-serverconfig={config}"));
+            }
+            (configuration, args)
+        })
+        .collect()
+}
+
+/// Outcome of packaging one matrix combination.
+#[derive(Debug)]
+pub struct VariantResult {
+    pub variant: BuildVariant,
+    pub passed: bool,
+    pub duration: Duration,
+}
+
+impl RunResult for VariantResult {
+    fn name(&self) -> String {
+        self.variant.to_string()
+    }
+
+    fn passed(&self) -> bool {
+        self.passed
+    }
+
+    fn duration(&self) -> Duration {
+        self.duration
+    }
+}
+
+pub fn print_summary(results: &[VariantResult]) {
+    workspace::print_summary(results, |passed, total| {
+        format!("{passed} of {total} combinations packaged")
+    });
+}
+
+impl FromStr for BuildVariant {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (platform, configuration) = match value.split_once(':') {
+            Some((platform, configuration)) => {
+                let parsed = Configuration::value_variants()
+                    .iter()
+                    .find(|c| c.as_str().eq_ignore_ascii_case(configuration))
+                    .ok_or_else(|| format!("`{configuration}` is not a build configuration"))?;
+                (platform, *parsed)
+            }
+            None => (value, Configuration::default()),
+        };
+        if platform.is_empty() {
+            return Err(format!("`{value}` has no platform"));
+        }
+        Ok(BuildVariant::new(platform, configuration))
+    }
+}
+
+impl TryFrom<String> for BuildVariant {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<BuildVariant> for String {
+    fn from(variant: BuildVariant) -> Self {
+        variant.to_string()
+    }
+}
+
+impl fmt::Display for BuildVariant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}",
+            self.full_platform(),
+            self.configuration.as_str()
+        )
+    }
+}
+
+/// Starting set of the packaging options.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BuildPreset {
+    /// Pak files, the same as without a preset.
+    Dev,
+    /// Shipping build for distribution, with compressed I/O store containers and prerequisites.
+    Release,
+    /// Quick iteration on content, cooking only the changed assets into loose files.
+    Iterate,
+}
+
+impl BuildPreset {
+    /// Configuration used when none is given.
+    pub fn configuration(&self) -> Option<Configuration> {
+        match self {
+            BuildPreset::Release => Some(Configuration::Shipping),
+            BuildPreset::Dev | BuildPreset::Iterate => None,
+        }
+    }
+}
+
+/// Steps and options of `BuildCookRun`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageOptions {
+    pub pak: bool,
+    pub iostore: bool,
+    pub compressed: bool,
+    pub prereqs: bool,
+    pub distribution: bool,
+    pub iterative_cooking: bool,
+    pub skip_build: bool,
+    pub skip_cook: bool,
+    /// Maps to cook, all maps from the project settings when empty.
+    pub maps: Vec<String>,
+    pub extra_args: Vec<String>,
+}
+
+impl Default for PackageOptions {
+    fn default() -> Self {
+        Self::from_preset(BuildPreset::Dev)
+    }
+}
+
+impl PackageOptions {
+    pub fn from_preset(preset: BuildPreset) -> Self {
+        let release = preset == BuildPreset::Release;
+        PackageOptions {
+            pak: preset != BuildPreset::Iterate,
+            iostore: release,
+            compressed: release,
+            prereqs: release,
+            distribution: release,
+            iterative_cooking: preset == BuildPreset::Iterate,
+            skip_build: false,
+            skip_cook: false,
+            maps: vec![],
+            extra_args: vec![],
+        }
+    }
+
+    /// Step arguments of `BuildCookRun`, without the project and platforms.
+    pub fn uat_args(&self, skip_build: bool) -> Vec<String> {
+        let mut args = vec!["-noP4", "-nodebuginfo"];
+        args.push(if self.skip_cook { "-skipcook" } else { "-cook" });
+        if self.iterative_cooking {
+            args.push("-iterativecooking");
+        }
+        args.push(if self.skip_build || skip_build {
+            "-skipbuild"
+        } else {
+            "-build"
+        });
+        args.extend(["-stage", "-archive"]);
+        for (enabled, arg) in [
+            (self.pak, "-pak"),
+            (self.iostore, "-iostore"),
+            (self.compressed, "-compressed"),
+            (self.prereqs, "-prereqs"),
+            (self.distribution, "-distribution"),
+        ] {
+            if enabled {
+                args.push(arg);
+            }
+        }
+        let mut args: Vec<String> = args.into_iter().map(str::to_owned).collect();
+        if !self.maps.is_empty() {
+            args.push(format!("-map={}", self.maps.join("+")));
+        }
+        args.extend(self.extra_args.iter().cloned());
+        args
+    }
+}
+
+/// Placeholders of the output path which change between builds.
+const BUILD_PLACEHOLDERS: [&str; 3] = ["version", "git_sha", "date"];
+
+/// Output directory of the build with `{name}` placeholders, e.g. `Builds/{platform}/{version}`.
+#[derive(Debug, Clone)]
+pub struct OutputTemplate {
+    components: Vec<String>,
+}
+
+impl OutputTemplate {
+    pub fn new(path: &Path) -> Self {
+        OutputTemplate {
+            components: path
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .collect(),
+        }
+    }
+
+    /// Whether the path contains the `{name}` placeholder.
+    pub fn uses(&self, name: &str) -> bool {
+        let placeholder = format!("{{{name}}}");
+        self.components.iter().any(|c| c.contains(&placeholder))
+    }
+
+    /// Path with the placeholders replaced by the values returned for their names.
+    pub fn expand(
+        &self,
+        mut value: impl FnMut(&str) -> Result<String, String>,
+    ) -> Result<PathBuf, String> {
+        let mut path = PathBuf::new();
+        for component in &self.components {
+            let mut expanded = String::new();
+            for segment in segments(component)? {
+                match segment {
+                    Segment::Text(text) => expanded.push_str(text),
+                    Segment::Placeholder(name) => expanded.push_str(&value(name)?),
+                }
+            }
+            path.push(expanded);
+        }
+        Ok(path)
+    }
+
+    /// Leading part of the path naming a single build, up to the last directory with
+    /// `{version}`, `{git_sha}` or `{date}`. `None` when every build uses the same directory.
+    pub fn build_dir(&self) -> Option<OutputTemplate> {
+        let last = self.components.iter().rposition(|c| {
+            BUILD_PLACEHOLDERS
+                .iter()
+                .any(|name| c.contains(&format!("{{{name}}}")))
+        })?;
+        Some(OutputTemplate {
+            components: self.components[..=last].to_vec(),
+        })
+    }
+
+    /// Pattern of the last directory name, with the values of the placeholders other than
+    /// `{version}`, `{git_sha}` and `{date}`, matching the names of the other builds.
+    pub fn name_pattern(
+        &self,
+        mut value: impl FnMut(&str) -> Result<String, String>,
+    ) -> Result<NamePattern, String> {
+        let mut parts = vec![];
+        for segment in segments(self.components.last().map_or("", String::as_str))? {
+            match segment {
+                Segment::Placeholder(name) if BUILD_PLACEHOLDERS.contains(&name) => {
+                    parts.push(PatternPart::Placeholder(name.to_owned()))
+                }
+                Segment::Placeholder(name) => parts.push(PatternPart::Text(value(name)?)),
+                Segment::Text(text) => parts.push(PatternPart::Text(text.to_owned())),
+            }
+        }
+        Ok(NamePattern { parts })
+    }
+}
+
+enum Segment<'a> {
+    Text(&'a str),
+    Placeholder(&'a str),
+}
+
+fn segments(component: &str) -> Result<Vec<Segment<'_>>, String> {
+    let mut segments = vec![];
+    let mut rest = component;
+    while let Some(start) = rest.find('{') {
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| format!("Unclosed placeholder in `{component}`"))?;
+        segments.push(Segment::Text(&rest[..start]));
+        segments.push(Segment::Placeholder(&rest[start + 1..start + end]));
+        rest = &rest[start + end + 1..];
+    }
+    segments.push(Segment::Text(rest));
+    Ok(segments)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PatternPart {
+    Text(String),
+    Placeholder(String),
+}
+
+/// Directory names a build output template produces, e.g. `Game-{version}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamePattern {
+    parts: Vec<PatternPart>,
+}
+
+impl NamePattern {
+    pub fn matches(&self, name: &str) -> bool {
+        fn matches_parts(parts: &[PatternPart], name: &str) -> bool {
+            match parts.split_first() {
+                None => name.is_empty(),
+                Some((PatternPart::Text(text), rest)) => name
+                    .strip_prefix(text.as_str())
+                    .is_some_and(|name| matches_parts(rest, name)),
+                Some((PatternPart::Placeholder(placeholder), rest)) => (1..=name.len())
+                    .filter(|end| name.is_char_boundary(*end))
+                    .any(|end| {
+                        is_placeholder_value(placeholder, &name[..end])
+                            && matches_parts(rest, &name[end..])
+                    }),
+            }
+        }
+        matches_parts(&self.parts, name)
+    }
+}
+
+/// Whether the text looks like a value of the `{version}`, `{git_sha}` or `{date}` placeholder.
+fn is_placeholder_value(placeholder: &str, text: &str) -> bool {
+    match placeholder {
+        "git_sha" => text.len() == 7 && text.chars().all(|c| c.is_ascii_hexdigit()),
+        "date" => {
+            text.len() == 10
+                && text.char_indices().all(|(i, c)| {
+                    if i == 4 || i == 7 {
+                        c == '-'
+                    } else {
+                        c.is_ascii_digit()
+                    }
+                })
+        }
+        "version" => {
+            text.starts_with(|c: char| c.is_ascii_digit())
+                && text
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+' | '_'))
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variants(values: &[&str]) -> Vec<BuildVariant> {
+        values.iter().map(|v| v.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn parses_and_prints_variants() {
+        let variant: BuildVariant = "LinuxServer:shipping".parse().unwrap();
+        assert_eq!(variant.to_string(), "LinuxServer:Shipping");
+        assert!(variant.server);
+        assert_eq!(
+            "Win64".parse::<BuildVariant>().unwrap().dir_name(),
+            "Win64-Development"
+        );
+        assert!("Win64:Fast".parse::<BuildVariant>().is_err());
+    }
+
+    #[test]
+    fn shares_builds_per_configuration() {
+        let args = shared_build_args(&variants(&[
+            "Win64:Shipping",
+            "Linux:Development",
+            "LinuxServer:Shipping",
+        ]));
+        assert_eq!(
+            args,
+            [
+                (
+                    Configuration::Shipping,
+                    vec![
+                        "-platform=Win64",
+                        "-clientconfig=Shipping",
+                        "-server",
+                        "-serverplatform=Linux",
+                        "-serverconfig=Shipping",
+                    ]
+                ),
+                (
+                    Configuration::Development,
+                    vec!["-platform=Linux", "-clientconfig=Development"]
+                ),
+            ]
+            .map(|(c, args)| (c, args.into_iter().map(str::to_owned).collect::<Vec<_>>()))
+        );
+    }
+
+    fn placeholder(name: &str) -> Result<String, String> {
+        match name {
+            "project" => Ok("MyGame".to_owned()),
+            "platform" => Ok("Win64".to_owned()),
+            "version" => Ok("1.2.0".to_owned()),
+            "git_sha" => Ok("a1b2c3d".to_owned()),
+            _ => Err(format!("Unknown placeholder `{{{name}}}`")),
+        }
+    }
+
+    #[test]
+    fn expands_output_template() {
+        let template = OutputTemplate::new(Path::new("Builds/{platform}/{project}-{version}"));
+        assert_eq!(
+            template.expand(placeholder).unwrap(),
+            Path::new("Builds/Win64/MyGame-1.2.0")
+        );
+        assert!(OutputTemplate::new(Path::new("Builds/{nope}"))
+            .expand(placeholder)
+            .is_err());
+        assert!(OutputTemplate::new(Path::new("Builds/{version"))
+            .expand(placeholder)
+            .is_err());
+    }
+
+    #[test]
+    fn finds_build_directory() {
+        let template = OutputTemplate::new(Path::new("Builds/{version}-{git_sha}/{platform}"));
+        assert_eq!(
+            template.build_dir().unwrap().expand(placeholder).unwrap(),
+            Path::new("Builds/1.2.0-a1b2c3d")
+        );
+        assert!(OutputTemplate::new(Path::new("Builds/{platform}"))
+            .build_dir()
+            .is_none());
+    }
+
+    #[test]
+    fn matches_only_names_of_other_builds() {
+        let template = OutputTemplate::new(Path::new("Builds/{platform}-{version}-{git_sha}"));
+        let pattern = template.name_pattern(placeholder).unwrap();
+        assert!(pattern.matches("Win64-1.2.0-a1b2c3d"));
+        assert!(pattern.matches("Win64-0.9.1-rc.1-0f0f0f0"));
+        assert!(!pattern.matches("Linux-1.2.0-a1b2c3d"));
+        assert!(!pattern.matches("Win64-latest-a1b2c3d"));
+
+        let bare = OutputTemplate::new(Path::new("{date}"))
+            .name_pattern(placeholder)
+            .unwrap();
+        assert!(bare.matches("2026-10-18"));
+        assert!(!bare.matches("Source"));
+        assert!(!bare.matches("Content"));
+    }
+}
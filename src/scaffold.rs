@@ -0,0 +1,477 @@
+use std::{
+    error::Error,
+    io,
+    path::{Path, PathBuf},
+};
+
+use clap::ValueEnum;
+
+use crate::{
+    build_rules, uplugin,
+    uproject::{LoadingPhase, Module, ModuleType},
+};
+
+/// Directories that are never copied from a template.
+const SKIPPED_DIRS: [&str; 6] = [
+    "Binaries",
+    "Intermediate",
+    "Saved",
+    "DerivedDataCache",
+    ".vs",
+    ".idea",
+];
+
+/// Template metadata files that should not end up in the created project.
+const SKIPPED_FILES: [&str; 1] = ["TemplateDefs.ini"];
+
+/// Extensions of the files in which template identifiers get replaced.
+const TEXT_EXTENSIONS: [&str; 9] = [
+    "uproject", "uplugin", "cpp", "h", "cs", "ini", "json", "txt", "md",
+];
+
+/// Unreal refuses to create projects with longer names.
+const MAX_PROJECT_NAME_LEN: usize = 20;
+
+/// Checks that the name can be used as a project, module or class identifier.
+pub fn validate_identifier(name: &str) -> Result<(), Box<dyn Error>> {
+    let mut chars = name.chars();
+    let Some(first) = chars.next() else {
+        return Err("Name cannot be empty".into());
+    };
+    if !first.is_ascii_alphabetic() {
+        return Err(format!("Name `{name}` has to start with a letter").into());
+    }
+    if !chars.all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(format!("Name `{name}` can contain only letters, digits and `_`").into());
+    }
+    Ok(())
+}
+
+pub fn validate_project_name(name: &str) -> Result<(), Box<dyn Error>> {
+    validate_identifier(name)?;
+    if name.len() > MAX_PROJECT_NAME_LEN {
+        return Err(format!(
+            "Project name `{name}` is longer than {MAX_PROJECT_NAME_LEN} characters"
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Replaces the template identifiers with new ones, including the upper case variant used by the `_API` macros.
+pub fn replace_identifiers(text: &str, old: &str, new: &str) -> String {
+    text.replace(old, new)
+        .replace(&old.to_uppercase(), &new.to_uppercase())
+}
+
+/// Recursively copies the template directory, renaming the files and identifiers from `old` to `new`.
+///
+/// Files inside `Content` are copied verbatim, renaming assets would break the references between them.
+pub fn copy_template(src: &Path, dst: &Path, old: &str, new: &str) -> io::Result<Vec<PathBuf>> {
+    let mut created = Vec::new();
+    copy_dir(src, dst, old, new, false, &mut created)?;
+    Ok(created)
+}
+
+fn copy_dir(
+    src: &Path,
+    dst: &Path,
+    old: &str,
+    new: &str,
+    verbatim: bool,
+    created: &mut Vec<PathBuf>,
+) -> io::Result<()> {
+    std::fs::create_dir_all(dst)?;
+    for entry in std::fs::read_dir(src)? {
+        let entry = entry?;
+        let path = entry.path();
+        let file_name = entry.file_name().to_string_lossy().into_owned();
+        let target_name = if verbatim {
+            file_name.clone()
+        } else {
+            file_name.replace(old, new)
+        };
+        let target = dst.join(target_name);
+
+        if path.is_dir() {
+            if SKIPPED_DIRS.contains(&file_name.as_str()) {
+                continue;
+            }
+            let verbatim = verbatim || file_name == "Content";
+            copy_dir(&path, &target, old, new, verbatim, created)?;
+            continue;
+        }
+        if SKIPPED_FILES.contains(&file_name.as_str()) {
+            continue;
+        }
+
+        let is_text = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| TEXT_EXTENSIONS.contains(&e));
+        if is_text && !verbatim {
+            let content = std::fs::read_to_string(&path)?;
+            std::fs::write(&target, replace_identifiers(&content, old, new))?;
+        } else {
+            std::fs::copy(&path, &target)?;
+        }
+        created.push(target);
+    }
+    Ok(())
+}
+
+fn build_cs(name: &str, private_deps: &[&str]) -> String {
+    let private_deps = private_deps
+        .iter()
+        .map(|d| format!("\"{d}\""))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        r#"using UnrealBuildTool;
+
+public class {name} : ModuleRules
+{{
+	public {name}(ReadOnlyTargetRules Target) : base(Target)
+	{{
+		PCHUsage = PCHUsageMode.UseExplicitOrSharedPCHs;
+
+		PublicDependencyModuleNames.AddRange(new string[] {{ "Core", "CoreUObject", "Engine" }});
+
+		PrivateDependencyModuleNames.AddRange(new string[] {{ {private_deps} }});
+	}}
+}}
+"#
+    )
+}
+
+fn module_header(name: &str, private_members: &str) -> String {
+    format!(
+        r#"#pragma once
+
+#include "CoreMinimal.h"
+#include "Modules/ModuleManager.h"
+
+class F{name}Module : public IModuleInterface
+{{
+public:
+	virtual void StartupModule() override;
+	virtual void ShutdownModule() override;
+{private_members}}};
+"#
+    )
+}
+
+/// Files of a new C++ module, paths are relative to the `Source` directory.
+pub fn module_files(name: &str, module_type: ModuleType) -> Vec<(PathBuf, String)> {
+    let private_deps: &[&str] = if module_type.is_editor_only() {
+        &["UnrealEd"]
+    } else {
+        &[]
+    };
+    let source = format!(
+        r#"#include "{name}.h"
+
+void F{name}Module::StartupModule()
+{{
+}}
+
+void F{name}Module::ShutdownModule()
+{{
+}}
+
+IMPLEMENT_MODULE(F{name}Module, {name})
+"#
+    );
+    let dir = PathBuf::from(name);
+    vec![
+        (
+            dir.join(format!("{name}.Build.cs")),
+            build_cs(name, private_deps),
+        ),
+        (
+            dir.join("Public").join(format!("{name}.h")),
+            module_header(name, ""),
+        ),
+        (dir.join("Private").join(format!("{name}.cpp")), source),
+    ]
+}
+
+/// Editor module adding a button to the level editor toolbar.
+fn editor_toolbar_module_files(name: &str) -> Vec<(PathBuf, String)> {
+    let private_members = "\nprivate:\n\tvoid RegisterMenus();\n\tvoid PluginButtonClicked();\n";
+    let source = format!(
+        r#"#include "{name}.h"
+#include "Misc/MessageDialog.h"
+#include "Styling/AppStyle.h"
+#include "ToolMenus.h"
+
+#define LOCTEXT_NAMESPACE "F{name}Module"
+
+void F{name}Module::StartupModule()
+{{
+	UToolMenus::RegisterStartupCallback(FSimpleMulticastDelegate::FDelegate::CreateRaw(this, &F{name}Module::RegisterMenus));
+}}
+
+void F{name}Module::ShutdownModule()
+{{
+	UToolMenus::UnRegisterStartupCallback(this);
+	UToolMenus::UnregisterOwner(this);
+}}
+
+void F{name}Module::RegisterMenus()
+{{
+	FToolMenuOwnerScoped OwnerScoped(this);
+	UToolMenu* ToolbarMenu = UToolMenus::Get()->ExtendMenu("LevelEditor.LevelEditorToolBar.PlayToolBar");
+	FToolMenuSection& Section = ToolbarMenu->FindOrAddSection("PluginTools");
+	Section.AddEntry(FToolMenuEntry::InitToolBarButton(
+		"{name}",
+		FUIAction(FExecuteAction::CreateRaw(this, &F{name}Module::PluginButtonClicked)),
+		LOCTEXT("{name}Label", "{name}"),
+		LOCTEXT("{name}Tooltip", "Run {name}"),
+		FSlateIcon(FAppStyle::GetAppStyleSetName(), "Icons.Settings")));
+}}
+
+void F{name}Module::PluginButtonClicked()
+{{
+	FMessageDialog::Open(EAppMsgType::Ok, LOCTEXT("{name}Clicked", "{name} button clicked"));
+}}
+
+#undef LOCTEXT_NAMESPACE
+
+IMPLEMENT_MODULE(F{name}Module, {name})
+"#
+    );
+    let deps = ["Slate", "SlateCore", "ToolMenus", "UnrealEd"];
+    let dir = PathBuf::from(name);
+    vec![
+        (dir.join(format!("{name}.Build.cs")), build_cs(name, &deps)),
+        (
+            dir.join("Public").join(format!("{name}.h")),
+            module_header(name, private_members),
+        ),
+        (dir.join("Private").join(format!("{name}.cpp")), source),
+    ]
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum PluginTemplate {
+    /// Plugin with a single runtime module.
+    #[default]
+    Blank,
+    /// Plugin without code that only contains assets.
+    ContentOnly,
+    /// Plugin with an editor module adding a button to the level editor toolbar.
+    EditorToolbar,
+}
+
+/// Files of a new plugin, paths are relative to the plugin directory.
+pub fn plugin_files(name: &str, template: PluginTemplate) -> io::Result<Vec<(PathBuf, String)>> {
+    let (module_type, sources) = match template {
+        PluginTemplate::Blank => (
+            Some(ModuleType::Runtime),
+            module_files(name, ModuleType::Runtime),
+        ),
+        PluginTemplate::ContentOnly => (None, vec![]),
+        PluginTemplate::EditorToolbar => {
+            (Some(ModuleType::Editor), editor_toolbar_module_files(name))
+        }
+    };
+    let modules = module_type
+        .map(|t| vec![Module::new(name, t, LoadingPhase::Default)])
+        .unwrap_or_default();
+    let descriptor = uplugin::Config::new(name, modules, template == PluginTemplate::ContentOnly);
+
+    let mut files = vec![(
+        PathBuf::from(format!("{name}.uplugin")),
+        uplugin::to_string(&descriptor)?,
+    )];
+    if template == PluginTemplate::ContentOnly {
+        files.push((PathBuf::from("Content").join(".gitkeep"), String::new()));
+    }
+    files.extend(
+        sources
+            .into_iter()
+            .map(|(path, content)| (PathBuf::from("Source").join(path), content)),
+    );
+    Ok(files)
+}
+
+/// Adds the module to the `ExtraModuleNames` of the `*.Target.cs` file content.
+///
+/// Returns `None` when the module is already there and an error when the file does not list
+/// its modules in a known way.
+pub fn add_extra_module_name(target_cs: &str, module: &str) -> Result<Option<String>, String> {
+    if build_rules::list_entries(target_cs, "ExtraModuleNames")
+        .iter()
+        .any(|m| m == module)
+    {
+        return Ok(None);
+    }
+    let quoted = format!("\"{module}\"");
+    let unknown = || "no `ExtraModuleNames.Add` or `ExtraModuleNames.AddRange` found".to_owned();
+    // Positions in the code without comments are the same as in the file content.
+    let code = build_rules::strip_comments(target_cs);
+    if let Some(start) = code.find("ExtraModuleNames.AddRange") {
+        let close = start + code[start..].find('}').ok_or_else(unknown)?;
+        let before = target_cs[..close].trim_end();
+        let separator = if before.ends_with('{') { " " } else { ", " };
+        return Ok(Some(format!(
+            "{before}{separator}{quoted} {}",
+            &target_cs[close..]
+        )));
+    }
+    let start = code.rfind("ExtraModuleNames.Add(").ok_or_else(unknown)?;
+    let line_start = target_cs[..start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = start + target_cs[start..].find('\n').ok_or_else(unknown)?;
+    let indent = &target_cs[line_start..start];
+    Ok(Some(format!(
+        "{}\n{indent}ExtraModuleNames.Add({quoted});{}",
+        &target_cs[..line_end],
+        &target_cs[line_end..]
+    )))
+}
+
+/// Base types that `add class` can generate code for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ParentClass {
+    #[value(name = "AActor")]
+    Actor,
+    #[value(name = "UActorComponent")]
+    ActorComponent,
+    #[value(name = "UObject")]
+    Object,
+    #[value(name = "USubsystem")]
+    Subsystem,
+    #[value(name = "UGameInstanceSubsystem")]
+    GameInstanceSubsystem,
+    #[value(name = "UWorldSubsystem")]
+    WorldSubsystem,
+    /// Plain `USTRUCT` without a parent.
+    #[value(name = "FStruct")]
+    Struct,
+}
+
+impl ParentClass {
+    pub fn prefix(&self) -> char {
+        match self {
+            ParentClass::Actor => 'A',
+            ParentClass::Struct => 'F',
+            _ => 'U',
+        }
+    }
+
+    fn class_name(&self) -> Option<&'static str> {
+        match self {
+            ParentClass::Actor => Some("AActor"),
+            ParentClass::ActorComponent => Some("UActorComponent"),
+            ParentClass::Object => Some("UObject"),
+            ParentClass::Subsystem => Some("USubsystem"),
+            ParentClass::GameInstanceSubsystem => Some("UGameInstanceSubsystem"),
+            ParentClass::WorldSubsystem => Some("UWorldSubsystem"),
+            ParentClass::Struct => None,
+        }
+    }
+
+    fn include(&self) -> Option<&'static str> {
+        match self {
+            ParentClass::Actor => Some("GameFramework/Actor.h"),
+            ParentClass::ActorComponent => Some("Components/ActorComponent.h"),
+            ParentClass::Object => Some("UObject/Object.h"),
+            ParentClass::Subsystem => Some("Subsystems/Subsystem.h"),
+            ParentClass::GameInstanceSubsystem => Some("Subsystems/GameInstanceSubsystem.h"),
+            ParentClass::WorldSubsystem => Some("Subsystems/WorldSubsystem.h"),
+            ParentClass::Struct => None,
+        }
+    }
+
+    fn has_constructor(&self) -> bool {
+        matches!(self, ParentClass::Actor | ParentClass::ActorComponent)
+    }
+}
+
+/// Header and source file of a new class, paths are relative to the module directory.
+pub fn class_files(module: &str, name: &str, parent: ParentClass) -> Vec<(PathBuf, String)> {
+    let api = format!("{}_API", module.to_uppercase());
+    let full_name = format!("{}{name}", parent.prefix());
+    let mut header = String::from("#pragma once\n\n#include \"CoreMinimal.h\"\n");
+    if let Some(include) = parent.include() {
+        header.push_str(&format!("#include \"{include}\"\n"));
+    }
+    header.push_str(&format!("#include \"{name}.generated.h\"\n\n"));
+
+    let mut source = format!("#include \"{name}.h\"\n");
+    match parent.class_name() {
+        None => header.push_str(&format!(
+            "USTRUCT(BlueprintType)\nstruct {api} {full_name}\n{{\n\tGENERATED_BODY()\n}};\n"
+        )),
+        Some(parent_name) => {
+            let specifiers = match parent {
+                ParentClass::ActorComponent => {
+                    "ClassGroup=(Custom), meta=(BlueprintSpawnableComponent)"
+                }
+                _ => "",
+            };
+            header.push_str(&format!(
+                "UCLASS({specifiers})\nclass {api} {full_name} : public {parent_name}\n{{\n\tGENERATED_BODY()\n"
+            ));
+            if parent.has_constructor() {
+                header.push_str(&format!("\npublic:\n\t{full_name}();\n"));
+                source.push_str(&format!("\n{full_name}::{full_name}()\n{{\n}}\n"));
+            }
+            header.push_str("};\n");
+        }
+    }
+
+    vec![
+        (PathBuf::from("Public").join(format!("{name}.h")), header),
+        (PathBuf::from("Private").join(format!("{name}.cpp")), source),
+    ]
+}
+
+/// Recursively searches the directory for a file with the given name.
+pub fn find_file_named(dir: &Path, file_name: &str) -> Option<PathBuf> {
+    for entry in std::fs::read_dir(dir).ok()?.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if path.is_dir() {
+            if let Some(found) = find_file_named(&path, file_name) {
+                return Some(found);
+            }
+        } else if entry.file_name() == file_name {
+            return Some(path);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adds_module_to_add_range() {
+        let target = "ExtraModuleNames.AddRange(new string[] { \"MyGame\" });";
+        assert_eq!(
+            add_extra_module_name(target, "MyGameUI").unwrap().unwrap(),
+            "ExtraModuleNames.AddRange(new string[] { \"MyGame\", \"MyGameUI\" });"
+        );
+    }
+
+    #[test]
+    fn adds_module_after_last_add() {
+        let target = "\t\tExtraModuleNames.Add(\"MyGame\");\n\t}\n";
+        assert_eq!(
+            add_extra_module_name(target, "MyGameUI").unwrap().unwrap(),
+            "\t\tExtraModuleNames.Add(\"MyGame\");\n\t\tExtraModuleNames.Add(\"MyGameUI\");\n\t}\n"
+        );
+    }
+
+    #[test]
+    fn tells_listed_module_from_unknown_format() {
+        let target = "ExtraModuleNames.Add(\"MyGame\");\n";
+        assert_eq!(add_extra_module_name(target, "MyGame"), Ok(None));
+        let target =
+            "// \"MyGameUI\" is added by the game\nExtraModuleNames.Add(\"MyGameUIEditor\");\n";
+        assert!(add_extra_module_name(target, "MyGameUI").unwrap().is_some());
+        assert!(add_extra_module_name("class MyGameTarget {}", "MyGame").is_err());
+    }
+}
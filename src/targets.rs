@@ -0,0 +1,257 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::build_rules;
+
+/// Unreal target types, as declared by `Type = TargetType.X` in the `*.Target.cs` files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TargetType {
+    Game,
+    Editor,
+    Client,
+    Server,
+    Program,
+}
+
+impl TargetType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TargetType::Game => "Game",
+            TargetType::Editor => "Editor",
+            TargetType::Client => "Client",
+            TargetType::Server => "Server",
+            TargetType::Program => "Program",
+        }
+    }
+}
+
+/// Build configurations accepted by UnrealBuildTool.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+pub enum Configuration {
+    Debug,
+    DebugGame,
+    #[default]
+    Development,
+    Test,
+    Shipping,
+}
+
+impl Configuration {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Configuration::Debug => "Debug",
+            Configuration::DebugGame => "DebugGame",
+            Configuration::Development => "Development",
+            Configuration::Test => "Test",
+            Configuration::Shipping => "Shipping",
+        }
+    }
+}
+
+/// Target type assigned by `Type = TargetType.X;` in the `*.Target.cs` file content.
+pub fn declared_type(target_cs: &str) -> Option<TargetType> {
+    const PREFIX: &str = "TargetType.";
+    let code = build_rules::strip_comments(target_cs);
+    code.match_indices(PREFIX).find_map(|(i, _)| {
+        let field = code[..i]
+            .trim_end()
+            .strip_suffix('=')?
+            .trim_end()
+            .strip_suffix("Type")?;
+        if field.ends_with(|c: char| c.is_alphanumeric() || c == '_') {
+            return None;
+        }
+        let name: String = code[i + PREFIX.len()..]
+            .chars()
+            .take_while(|c| c.is_alphanumeric())
+            .collect();
+        TargetType::value_variants()
+            .iter()
+            .find(|t| t.as_str() == name)
+            .copied()
+    })
+}
+
+/// `*.Target.cs` files from the project `Source` directory.
+pub fn find_target_files(source_dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(source_dir)?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.ends_with(".Target.cs"))
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Target declared by a `*.Target.cs` file.
+#[derive(Debug, Clone)]
+pub struct Target {
+    /// Name used by UnrealBuildTool, the class name without the `Target` suffix.
+    pub name: String,
+    pub class_name: String,
+    pub target_type: TargetType,
+    pub path: PathBuf,
+}
+
+/// Reads the name of the `TargetRules` class and the declared target type from the file content.
+pub fn parse_target(path: &Path, target_cs: &str) -> Option<Target> {
+    let target_type = declared_type(target_cs)?;
+    let class_name = target_cs.match_indices("class ").find_map(|(pos, _)| {
+        let rest = &target_cs[pos + "class ".len()..];
+        let name: String = rest
+            .chars()
+            .take_while(|c| c.is_alphanumeric() || *c == '_')
+            .collect();
+        let header = &rest[..rest.find('{')?];
+        (!name.is_empty() && header.contains("TargetRules")).then_some(name)
+    })?;
+    let name = class_name
+        .strip_suffix("Target")
+        .unwrap_or(&class_name)
+        .to_owned();
+    Some(Target {
+        name,
+        class_name,
+        target_type,
+        path: path.to_path_buf(),
+    })
+}
+
+/// Targets declared in the project `Source` directory.
+pub fn find_targets(project_path: &Path) -> io::Result<Vec<Target>> {
+    let source_dir = project_path.with_file_name("Source");
+    if !source_dir.exists() {
+        return Ok(vec![]);
+    }
+    let mut targets = vec![];
+    for file in find_target_files(&source_dir)? {
+        let content = std::fs::read_to_string(&file)?;
+        targets.extend(parse_target(&file, &content));
+    }
+    Ok(targets)
+}
+
+/// Picks the target of the given type.
+///
+/// When `requested` is set it has to match the name of a target of the type, a Client target is accepted
+/// in place of a Game one. Otherwise the only target of the type is used.
+/// With several targets of the same type the one named after the project wins, for example `MyGameEditor`.
+pub fn select_target<'a>(
+    targets: &'a [Target],
+    target_type: TargetType,
+    project_name: &str,
+    requested: Option<&str>,
+) -> Result<&'a Target, String> {
+    if let Some(requested) = requested {
+        let target = targets
+            .iter()
+            .find(|t| t.name == requested)
+            .ok_or_else(|| format!("Target `{requested}` is not declared in the project"))?;
+        let accepted = target.target_type == target_type
+            || (target_type == TargetType::Game && target.target_type == TargetType::Client);
+        if !accepted {
+            return Err(format!(
+                "Target `{requested}` is a {} target, expected a {} target",
+                target.target_type.as_str(),
+                target_type.as_str()
+            ));
+        }
+        return Ok(target);
+    }
+    let candidates: Vec<&Target> = targets
+        .iter()
+        .filter(|t| t.target_type == target_type)
+        .collect();
+    match candidates.as_slice() {
+        [] => Err(format!(
+            "Project does not declare a {} target",
+            target_type.as_str()
+        )),
+        [target] => Ok(target),
+        _ => {
+            let suffix = if target_type == TargetType::Game {
+                ""
+            } else {
+                target_type.as_str()
+            };
+            let expected = format!("{project_name}{suffix}");
+            candidates
+                .iter()
+                .find(|t| t.name == expected)
+                .copied()
+                .ok_or_else(|| {
+                    let names: Vec<&str> = candidates.iter().map(|t| t.name.as_str()).collect();
+                    format!(
+                        "Project declares several {} targets: {}, pick one with `--target`",
+                        target_type.as_str(),
+                        names.join(", ")
+                    )
+                })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_assigned_target_type() {
+        let target_cs = r#"
+public class MyGameEditorTarget : TargetRules
+{
+	// Unlike TargetType.Game, it builds the editor modules.
+	public MyGameEditorTarget(TargetInfo Target) : base(Target)
+	{
+		Type = TargetType.Editor;
+	}
+}"#;
+        assert_eq!(declared_type(target_cs), Some(TargetType::Editor));
+    }
+
+    #[test]
+    fn checks_type_of_requested_target() {
+        let target = |name: &str, target_type| Target {
+            name: name.to_owned(),
+            class_name: format!("{name}Target"),
+            target_type,
+            path: PathBuf::from(format!("{name}.Target.cs")),
+        };
+        let targets = [
+            target("MyGame", TargetType::Game),
+            target("MyGameClient", TargetType::Client),
+            target("MyGameEditor", TargetType::Editor),
+        ];
+        let select = |target_type, requested| {
+            select_target(&targets, target_type, "MyGame", Some(requested)).map(|t| &t.name)
+        };
+        assert_eq!(
+            select(TargetType::Editor, "MyGameEditor").unwrap(),
+            "MyGameEditor"
+        );
+        assert_eq!(
+            select(TargetType::Game, "MyGameClient").unwrap(),
+            "MyGameClient"
+        );
+        assert!(select(TargetType::Editor, "MyGame").is_err());
+        assert!(select(TargetType::Game, "MyGameEditor").is_err());
+    }
+
+    #[test]
+    fn ignores_comparisons_and_other_fields() {
+        let target_cs = r#"
+		if (Target.Type == TargetType.Server) {}
+		DefaultType = TargetType.Client;
+		Type=TargetType.Server;"#;
+        assert_eq!(declared_type(target_cs), Some(TargetType::Server));
+        assert_eq!(declared_type("/* Type = TargetType.Game; */"), None);
+    }
+}
@@ -0,0 +1,119 @@
+#![allow(non_snake_case)]
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::uproject::{self, Module, Plugin};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Config {
+    pub FileVersion: i32,
+    #[serde(default)]
+    pub Version: i32,
+    #[serde(default)]
+    pub VersionName: String,
+    #[serde(default)]
+    pub FriendlyName: String,
+    #[serde(default)]
+    pub Description: String,
+    #[serde(default)]
+    pub Category: String,
+    #[serde(default)]
+    pub CreatedBy: String,
+    #[serde(default)]
+    pub CanContainContent: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub Modules: Vec<Module>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub Plugins: Vec<Plugin>,
+    /// Fields that uec does not handle, kept so that rewriting the file does not lose them.
+    #[serde(flatten)]
+    pub Extra: Map<String, Value>,
+}
+
+impl Config {
+    pub fn new(name: &str, modules: Vec<Module>, can_contain_content: bool) -> Self {
+        Config {
+            FileVersion: 3,
+            Version: 1,
+            VersionName: "1.0".into(),
+            FriendlyName: name.into(),
+            Description: String::new(),
+            Category: "Other".into(),
+            CreatedBy: String::new(),
+            CanContainContent: can_contain_content,
+            Modules: modules,
+            Plugins: vec![],
+            Extra: Map::new(),
+        }
+    }
+}
+
+impl Config {
+    /// Whether the plugin is used without listing it in the `.uproject` file.
+    ///
+    /// Project plugins are enabled by default, engine plugins are not.
+    pub fn is_enabled_by_default(&self, is_project_plugin: bool) -> bool {
+        self.Extra
+            .get("EnabledByDefault")
+            .and_then(Value::as_bool)
+            .unwrap_or(is_project_plugin)
+    }
+}
+
+pub fn read_config<P: AsRef<Path>>(path: P) -> std::io::Result<Config> {
+    let mut file = File::open(path)?;
+    let mut data = String::new();
+    file.read_to_string(&mut data)?;
+    let config: Config = serde_json::from_str(&data)?;
+    Ok(config)
+}
+
+pub fn to_string(config: &Config) -> std::io::Result<String> {
+    uproject::to_descriptor_string(config)
+}
+
+/// Finds `.uplugin` files under the directory, mapping plugin names to their descriptor paths.
+///
+/// Plugins cannot be nested, so directories containing a descriptor are not searched further.
+pub fn find_plugins(dir: &Path) -> HashMap<String, PathBuf> {
+    let mut plugins = HashMap::new();
+    collect_plugins(dir, &mut plugins);
+    plugins
+}
+
+fn collect_plugins(dir: &Path, plugins: &mut HashMap<String, PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let entries: Vec<PathBuf> = entries.filter_map(|e| e.ok()).map(|e| e.path()).collect();
+    let descriptor = entries
+        .iter()
+        .find(|p| p.is_file() && p.extension().is_some_and(|e| e == "uplugin"));
+    if let Some(descriptor) = descriptor {
+        if let Some(name) = descriptor.file_stem().and_then(|s| s.to_str()) {
+            plugins.insert(name.to_owned(), descriptor.clone());
+        }
+        return;
+    }
+    for entry in entries.iter().filter(|p| p.is_dir()) {
+        collect_plugins(entry, plugins);
+    }
+}
+
+/// Finds the plugin under the directory that contains the given module.
+pub fn find_plugin_with_module(dir: &Path, module: &str) -> Option<(String, Config)> {
+    find_plugins(dir)
+        .into_iter()
+        .find_map(|(name, descriptor_path)| {
+            let descriptor = read_config(descriptor_path).ok()?;
+            descriptor
+                .Modules
+                .iter()
+                .any(|m| m.Name == module)
+                .then_some((name, descriptor))
+        })
+}
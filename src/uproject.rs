@@ -1,41 +1,173 @@
 #![allow(non_snake_case)]
+use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use std::fs::File;
-use std::io::Read;
+use std::io::{Read, Write};
+use std::path::Path;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Module {
     pub Name: String,
-    Type: String,
-    LoadingPhase: String,
+    pub Type: String,
+    pub LoadingPhase: String,
+    /// Fields that uec does not handle, kept so that rewriting the file does not lose them.
+    #[serde(flatten)]
+    pub Extra: Map<String, Value>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl Module {
+    pub fn new(name: &str, module_type: ModuleType, loading_phase: LoadingPhase) -> Self {
+        Module {
+            Name: name.to_owned(),
+            Type: module_type.as_str().to_owned(),
+            LoadingPhase: loading_phase.as_str().to_owned(),
+            Extra: Map::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ModuleType {
+    Runtime,
+    RuntimeNoCommandlet,
+    Developer,
+    Editor,
+    EditorNoCommandlet,
+    UncookedOnly,
+    ServerOnly,
+    ClientOnly,
+}
+
+impl ModuleType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ModuleType::Runtime => "Runtime",
+            ModuleType::RuntimeNoCommandlet => "RuntimeNoCommandlet",
+            ModuleType::Developer => "Developer",
+            ModuleType::Editor => "Editor",
+            ModuleType::EditorNoCommandlet => "EditorNoCommandlet",
+            ModuleType::UncookedOnly => "UncookedOnly",
+            ModuleType::ServerOnly => "ServerOnly",
+            ModuleType::ClientOnly => "ClientOnly",
+        }
+    }
+
+    /// Editor-only modules are not part of game, client or server targets.
+    pub fn is_editor_only(&self) -> bool {
+        matches!(
+            self,
+            ModuleType::Editor | ModuleType::EditorNoCommandlet | ModuleType::UncookedOnly
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum LoadingPhase {
+    EarliestPossible,
+    PostConfigInit,
+    PreEarlyLoadingScreen,
+    PreLoadingScreen,
+    PreDefault,
+    #[default]
+    Default,
+    PostDefault,
+    PostEngineInit,
+}
+
+impl LoadingPhase {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LoadingPhase::EarliestPossible => "EarliestPossible",
+            LoadingPhase::PostConfigInit => "PostConfigInit",
+            LoadingPhase::PreEarlyLoadingScreen => "PreEarlyLoadingScreen",
+            LoadingPhase::PreLoadingScreen => "PreLoadingScreen",
+            LoadingPhase::PreDefault => "PreDefault",
+            LoadingPhase::Default => "Default",
+            LoadingPhase::PostDefault => "PostDefault",
+            LoadingPhase::PostEngineInit => "PostEngineInit",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Plugin {
-    Name: String,
-    Enabled: bool,
-    TargetAllowList: Option<Vec<String>>,
+    pub Name: String,
+    pub Enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub TargetAllowList: Option<Vec<String>>,
+    #[serde(flatten)]
+    pub Extra: Map<String, Value>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl Plugin {
+    pub fn enabled(name: &str) -> Self {
+        Plugin {
+            Name: name.to_owned(),
+            Enabled: true,
+            TargetAllowList: None,
+            Extra: Map::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Config {
-    FileVersion: i32,
-    EngineAssociation: String,
-    Category: String,
-    Description: String,
-    Modules: Vec<Module>,
-    Plugins: Vec<Plugin>,
+    pub FileVersion: i32,
+    pub EngineAssociation: String,
+    #[serde(default)]
+    pub Category: String,
+    #[serde(default)]
+    pub Description: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub Modules: Vec<Module>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub Plugins: Vec<Plugin>,
+    #[serde(flatten)]
+    pub Extra: Map<String, Value>,
 }
 
-pub fn read_config(path: &str) -> std::io::Result<Config> {
+pub fn read_config<P: AsRef<Path>>(path: P) -> std::io::Result<Config> {
     let mut file = File::open(path)?;
     let mut data = String::new();
     file.read_to_string(&mut data)?;
     let config: Config = serde_json::from_str(&data)?;
     Ok(config)
 }
+
+/// Serializes the `.uproject` or `.uplugin` descriptor using the same tab indentation as the files generated by the editor.
+pub fn to_descriptor_string<T: Serialize>(descriptor: &T) -> std::io::Result<String> {
+    let mut data = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(b"\t");
+    let mut serializer = serde_json::Serializer::with_formatter(&mut data, formatter);
+    descriptor.serialize(&mut serializer)?;
+    data.push(b'\n');
+    String::from_utf8(data).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
+
+pub fn write_config<P: AsRef<Path>>(path: P, config: &Config) -> std::io::Result<()> {
+    File::create(path)?.write_all(to_descriptor_string(config)?.as_bytes())
+}
+
 impl Config {
-    pub fn find_editor_module(&self) -> Option<&Module> {
-        self.Modules.iter().find(|m| m.Type == "Editor")
+    pub fn find_module(&self, name: &str) -> Option<&Module> {
+        self.Modules.iter().find(|m| m.Name == name)
+    }
+
+    /// Enables the plugin, adding it to the list when needed.
+    ///
+    /// Returns `false` when the plugin was already enabled.
+    pub fn enable_plugin(&mut self, name: &str) -> bool {
+        match self.Plugins.iter_mut().find(|p| p.Name == name) {
+            Some(plugin) if plugin.Enabled => false,
+            Some(plugin) => {
+                plugin.Enabled = true;
+                true
+            }
+            None => {
+                self.Plugins.push(Plugin::enabled(name));
+                true
+            }
+        }
     }
 }
@@ -0,0 +1,165 @@
+//! Workspaces group several projects, so commands can be run on all of them at once.
+//!
+//! The `uec-workspace.toml` file lists the member directories relative to the file:
+//!
+//! ```toml
+//! members = ["Game", "Tests/TestProject", "Samples/Sample1"]
+//! ```
+
+use colour::{dark_green_ln, dark_green_ln_bold, e_red_ln, print_ln_bold};
+use serde::Deserialize;
+use std::{
+    error::Error,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+pub const WORKSPACE_FILE: &str = "uec-workspace.toml";
+
+#[derive(Debug, Deserialize)]
+pub struct Workspace {
+    /// Directories of the member projects, or paths to their `.uproject` files.
+    pub members: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Member {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+#[derive(Debug)]
+pub struct MemberResult {
+    pub name: String,
+    pub passed: bool,
+    pub duration: Duration,
+}
+
+/// Finds the workspace file in the directory or the closest of its parents.
+pub fn find_workspace(dir: &Path) -> Result<PathBuf, Box<dyn Error>> {
+    let dir =
+        std::fs::canonicalize(dir).map_err(|e| format!("Failed to open {}: {e}", dir.display()))?;
+    dir.ancestors()
+        .map(|d| d.join(WORKSPACE_FILE))
+        .find(|f| f.is_file())
+        .ok_or_else(|| {
+            format!(
+                "Could not find {WORKSPACE_FILE} in {} or any of its parents",
+                dir.display()
+            )
+            .into()
+        })
+}
+
+/// Reads the members of the workspace from the file.
+pub fn read_members(workspace_file: &Path) -> Result<Vec<Member>, Box<dyn Error>> {
+    let content = std::fs::read_to_string(workspace_file)?;
+    let workspace: Workspace = toml::from_str(&content)
+        .map_err(|e| format!("Failed to read {}: {e}", workspace_file.display()))?;
+    let root = workspace_file.parent().unwrap_or(Path::new("."));
+    let members = workspace
+        .members
+        .iter()
+        .map(|member| {
+            let path = root.join(member);
+            let name = if path.extension().is_some_and(|e| e == "uproject") {
+                path.file_stem()
+            } else {
+                path.file_name()
+            };
+            Member {
+                name: name.map_or_else(
+                    || member.display().to_string(),
+                    |n| n.to_string_lossy().into_owned(),
+                ),
+                path,
+            }
+        })
+        .collect();
+    Ok(members)
+}
+
+/// Runs the command for every member, one after another or each on its own thread.
+pub fn run<F>(members: &[Member], parallel: bool, command: F) -> Vec<MemberResult>
+where
+    F: Fn(&Member) -> bool + Sync,
+{
+    let run_member = |member: &Member| {
+        print_ln_bold!("[{}] {}", member.name, member.path.display());
+        let start = Instant::now();
+        let passed = command(member);
+        MemberResult {
+            name: member.name.clone(),
+            passed,
+            duration: start.elapsed(),
+        }
+    };
+    if !parallel {
+        return members.iter().map(run_member).collect();
+    }
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = members
+            .iter()
+            .map(|member| scope.spawn(|| run_member(member)))
+            .collect();
+        handles
+            .into_iter()
+            .zip(members)
+            .map(|(handle, member)| {
+                handle.join().unwrap_or_else(|_| MemberResult {
+                    name: member.name.clone(),
+                    passed: false,
+                    duration: Duration::ZERO,
+                })
+            })
+            .collect()
+    })
+}
+
+/// Outcome of one run of a command repeated for several projects or build variants.
+pub trait RunResult {
+    fn name(&self) -> String;
+    fn passed(&self) -> bool;
+    fn duration(&self) -> Duration;
+}
+
+impl RunResult for MemberResult {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn passed(&self) -> bool {
+        self.passed
+    }
+
+    fn duration(&self) -> Duration {
+        self.duration
+    }
+}
+
+/// Prints a `PASS` or `FAIL` line for every result, followed by the totals.
+///
+/// `totals` formats the totals line from the number of passed results and the number of all of them.
+pub fn print_summary<R: RunResult>(results: &[R], totals: impl Fn(usize, usize) -> String) {
+    println!();
+    for result in results {
+        let line = format!(
+            "{} {} ({:.1}s)",
+            if result.passed() { "PASS" } else { "FAIL" },
+            result.name(),
+            result.duration().as_secs_f32()
+        );
+        if result.passed() {
+            dark_green_ln!("{}", line);
+        } else {
+            e_red_ln!("{}", line);
+        }
+    }
+    let passed = results.iter().filter(|r| r.passed()).count();
+    let summary = totals(passed, results.len());
+    if passed == results.len() {
+        dark_green_ln_bold!("{}", summary);
+    } else {
+        e_red_ln!("{}", summary);
+    }
+}